@@ -0,0 +1,116 @@
+//! Parsing benchmarks
+//!
+//! Run with `cargo bench`. Each case reports the mean time per parse, the
+//! number of heap allocations a single parse performs and the heap bytes
+//! still held by its result, counted by a wrapping global allocator.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use json_parser::{extract_pointer, Document, ParseOptions, Value};
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        LIVE_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        LIVE_BYTES.fetch_add(new_size, Ordering::Relaxed);
+        LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn bench(name: &str, input: &str) {
+    bench_with(name, input, |input| Value::from_str(input).unwrap());
+}
+
+fn bench_with<T>(name: &str, input: &str, parse: impl Fn(&str) -> T) {
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes_before = LIVE_BYTES.load(Ordering::Relaxed);
+    let result = parse(black_box(input));
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
+    let retained = LIVE_BYTES.load(Ordering::Relaxed) - bytes_before;
+    drop(black_box(result));
+
+    let mut iterations = 0u32;
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(1) {
+        drop(black_box(parse(black_box(input))));
+        iterations += 1;
+    }
+    let mean = start.elapsed() / iterations;
+
+    println!(
+        "{:<24} {:>12.3?}/parse {:>10} allocations/parse {:>10} bytes retained ({} bytes input)",
+        name,
+        mean,
+        allocations,
+        retained,
+        input.len()
+    );
+}
+
+fn large_number_array(len: usize) -> String {
+    let numbers: Vec<String> = (0..len).map(|i| (i as f64 * 1.5).to_string()).collect();
+    format!("[{}]", numbers.join(","))
+}
+
+fn large_object(len: usize) -> String {
+    let members: Vec<String> = (0..len).map(|i| format!("\"key{}\":{}", i, i)).collect();
+    format!("{{{}}}", members.join(","))
+}
+
+fn array_of_records(len: usize) -> String {
+    let records: Vec<String> = (0..len)
+        .map(|i| {
+            format!(
+                r#"{{"id":{},"name":"user{}","active":{},"tags":["a","b","c"],"score":{}.5}}"#,
+                i,
+                i,
+                i % 2 == 0,
+                i
+            )
+        })
+        .collect();
+    format!("[{}]", records.join(","))
+}
+
+fn main() {
+    bench("number array (100k)", &large_number_array(100_000));
+    bench("object (10k keys)", &large_object(10_000));
+    bench("records (10k)", &array_of_records(10_000));
+
+    let records = array_of_records(10_000);
+    bench_with("records (10k) arena", &records, |input| {
+        Document::parse(input).unwrap()
+    });
+    let interned = ParseOptions {
+        intern_keys: true,
+        ..Default::default()
+    };
+    bench_with("records (10k) interned", &records, |input| {
+        Document::parse_with_options(input, &interned).unwrap()
+    });
+    bench_with("extract /9999/name", &records, |input| {
+        extract_pointer(input, "/9999/name").unwrap()
+    });
+}
@@ -0,0 +1,704 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::Range;
+
+use crate::parser::{Builder, Parser};
+use crate::ser::{write_number, write_string};
+use crate::{DuplicateKeys, ParseError, ParseOptions, SerializeOptions, Value};
+
+/// A parsed JSON document stored in a handful of flat buffers
+///
+/// Where a `Value` tree allocates every string, array and object separately,
+/// a `Document` keeps all nodes in one `Vec`, all string contents and keys
+/// in one `String`, and container children in two index tables. Parsing
+/// costs a few buffer growths instead of one allocation per node, and
+/// dropping the document frees everything at once.
+///
+/// Nodes are read through `NodeRef` handles borrowed from the document. Use
+/// `to_value` to convert to an owned `Value` tree when needed.
+///
+/// Object member lookup with `NodeRef::get` is a linear scan, which suits
+/// the small objects typical of records; repeated keys resolve to the last
+/// occurrence, matching `Value::from_str`.
+///
+/// A document displays as compact JSON with members in their original
+/// order. Parsed with `ParseOptions::keep_number_source`, it also keeps each
+/// number's digits, so `1.10` and `1e2` are written back unchanged.
+#[derive(Debug, Clone)]
+pub struct Document {
+    nodes: Vec<Node>,
+    /// Contents of every string and key, and of kept number sources, back
+    /// to back
+    text: String,
+    /// Element node ids; each array owns a contiguous range
+    elements: Vec<usize>,
+    /// Key ranges into `text` and value node ids; each object owns a
+    /// contiguous range
+    members: Vec<(Range<usize>, usize)>,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Null,
+    Boolean(bool),
+    /// The value and, with `keep_number_source`, its text in `text`
+    Number(f64, Option<Range<usize>>),
+    String(Range<usize>),
+    Array(Range<usize>),
+    Object(Range<usize>),
+}
+
+impl Document {
+    /// A document with no nodes yet, for the builder to fill. Not public:
+    /// every `Document` handed out has a root.
+    fn empty() -> Self {
+        Self {
+            nodes: Vec::new(),
+            text: String::new(),
+            elements: Vec::new(),
+            members: Vec::new(),
+        }
+    }
+
+    /// Parse a JSON string into a Document
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        Parser::new(input).parse_document()
+    }
+
+    /// Parse a JSON string into a Document using the given options
+    ///
+    /// Set `ParseOptions::intern_keys` to store repeated object keys once,
+    /// and `ParseOptions::keep_number_source` to keep the text of numbers.
+    /// `duplicate_keys` applies as it does for `Value`: `LastWins` keeps
+    /// every member but `get` finds the last, `Error` rejects the document,
+    /// and `Collect` stores one member per key, at its first occurrence,
+    /// whose value is an array of all the values when the key repeats.
+    pub fn parse_with_options(input: &str, options: &ParseOptions) -> Result<Self, ParseError> {
+        Parser::with_options(input, options.clone()).parse_document()
+    }
+
+    /// The top-level value of the document
+    pub fn root(&self) -> NodeRef<'_> {
+        // Children are stored before their parents, so the root comes last
+        NodeRef {
+            doc: self,
+            id: self.nodes.len() - 1,
+        }
+    }
+
+    /// Convert the whole document to an owned Value tree
+    pub fn to_value(&self) -> Value {
+        self.root().to_value()
+    }
+}
+
+/// A node borrowed from a Document
+#[derive(Debug, Clone, Copy)]
+pub struct NodeRef<'d> {
+    doc: &'d Document,
+    id: usize,
+}
+
+impl<'d> NodeRef<'d> {
+    fn node(&self) -> &'d Node {
+        &self.doc.nodes[self.id]
+    }
+
+    fn at(&self, id: usize) -> NodeRef<'d> {
+        NodeRef { doc: self.doc, id }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self.node(), Node::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.node() {
+            Node::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.node() {
+            Node::Number(n, _) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// The text a number was written with, such as `1.10`, when the
+    /// document was parsed with `ParseOptions::keep_number_source`
+    ///
+    /// `None` for other nodes, for documents parsed without the option, and
+    /// for `NaN` and `Infinity`, which are written as `null` like in `Value`.
+    pub fn number_source(&self) -> Option<&'d str> {
+        match self.node() {
+            Node::Number(_, Some(range)) => Some(&self.doc.text[range.clone()]),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&'d str> {
+        match self.node() {
+            Node::String(range) => Some(&self.doc.text[range.clone()]),
+            _ => None,
+        }
+    }
+
+    /// Number of elements or members; 0 for scalars
+    pub fn len(&self) -> usize {
+        match self.node() {
+            Node::Array(range) | Node::Object(range) => range.len(),
+            _ => 0,
+        }
+    }
+
+    /// True for scalars and empty containers
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Element `index` of an array
+    pub fn index(&self, index: usize) -> Option<NodeRef<'d>> {
+        match self.node() {
+            Node::Array(range) => self.doc.elements[range.clone()]
+                .get(index)
+                .map(|&id| self.at(id)),
+            _ => None,
+        }
+    }
+
+    /// Value of the member named `key` in an object
+    pub fn get(&self, key: &str) -> Option<NodeRef<'d>> {
+        self.members()
+            .filter(|(k, _)| *k == key)
+            .last()
+            .map(|(_, node)| node)
+    }
+
+    /// Elements of an array in order; empty for anything else
+    pub fn elements(&self) -> impl Iterator<Item = NodeRef<'d>> + 'd {
+        let this = *self;
+        let ids = match self.node() {
+            Node::Array(range) => &self.doc.elements[range.clone()],
+            _ => &[],
+        };
+        ids.iter().map(move |&id| this.at(id))
+    }
+
+    /// Members of an object in document order, including any repeated keys;
+    /// empty for anything else
+    pub fn members(&self) -> impl Iterator<Item = (&'d str, NodeRef<'d>)> + 'd {
+        let this = *self;
+        let members = match self.node() {
+            Node::Object(range) => &self.doc.members[range.clone()],
+            _ => &[],
+        };
+        members
+            .iter()
+            .map(move |(key, id)| (&this.doc.text[key.clone()], this.at(*id)))
+    }
+
+    /// The child at `index` of an array or object, with its key if the node
+    /// is an object
+    fn child(&self, index: usize) -> Option<(Option<&'d str>, NodeRef<'d>)> {
+        match self.node() {
+            Node::Array(range) => self.doc.elements[range.clone()]
+                .get(index)
+                .map(|id| (None, self.at(*id))),
+            Node::Object(range) => self.doc.members[range.clone()]
+                .get(index)
+                .map(|(key, id)| (Some(&self.doc.text[key.clone()]), self.at(*id))),
+            _ => None,
+        }
+    }
+
+    /// Convert this node and everything under it to an owned Value
+    ///
+    /// Walks the document with an explicit stack, so nesting depth is not
+    /// limited by the call stack.
+    pub fn to_value(&self) -> Value {
+        // Open containers, each with the position of its next child and the
+        // container value built so far
+        let mut stack: Vec<(NodeRef<'d>, usize, Value)> = Vec::new();
+        let mut next = Some(*self);
+        // A finished value waiting to be added to the innermost container
+        let mut finished = None;
+        loop {
+            if let Some(node) = next.take() {
+                match node.node() {
+                    Node::Null => finished = Some(Value::Null),
+                    Node::Boolean(b) => finished = Some(Value::Boolean(*b)),
+                    Node::Number(n, _) => finished = Some(Value::Number(*n)),
+                    Node::String(_) => {
+                        let s = node.as_str().unwrap_or_default().to_string();
+                        finished = Some(Value::String(s));
+                    }
+                    Node::Array(range) => {
+                        let elements = Vec::with_capacity(range.len());
+                        stack.push((node, 0, Value::Array(elements)));
+                    }
+                    Node::Object(range) => {
+                        let members = HashMap::with_capacity(range.len());
+                        stack.push((node, 0, Value::Object(members)));
+                    }
+                }
+            }
+
+            let Some((parent, index, container)) = stack.last_mut() else {
+                return finished.unwrap_or(Value::Null);
+            };
+            if let Some(value) = finished.take() {
+                match container {
+                    Value::Array(elements) => elements.push(value),
+                    Value::Object(members) => {
+                        let key = parent.child(*index - 1).and_then(|(key, _)| key);
+                        members.insert(key.unwrap_or_default().to_string(), value);
+                    }
+                    _ => unreachable!("only containers are pushed"),
+                }
+            }
+            match parent.child(*index) {
+                Some((_, child)) => {
+                    *index += 1;
+                    next = Some(child);
+                }
+                None => finished = stack.pop().map(|(_, _, container)| container),
+            }
+        }
+    }
+
+    /// Append this node as compact JSON, members in document order
+    ///
+    /// Like `to_value`, this keeps its own stack rather than recursing.
+    fn write_json(&self, out: &mut String) {
+        let options = SerializeOptions::default();
+        // Open containers with the position of their next child
+        let mut stack: Vec<(NodeRef<'d>, usize)> = Vec::new();
+        let mut next = Some(*self);
+        loop {
+            if let Some(node) = next.take() {
+                match node.node() {
+                    Node::Null => out.push_str("null"),
+                    Node::Boolean(b) => out.push_str(if *b { "true" } else { "false" }),
+                    Node::Number(n, source) => match source {
+                        Some(range) => out.push_str(&node.doc.text[range.clone()]),
+                        None => write_number(out, *n),
+                    },
+                    Node::String(_) => {
+                        write_string(out, node.as_str().unwrap_or_default(), &options)
+                    }
+                    Node::Array(_) => {
+                        out.push('[');
+                        stack.push((node, 0));
+                    }
+                    Node::Object(_) => {
+                        out.push('{');
+                        stack.push((node, 0));
+                    }
+                }
+            }
+
+            let Some((parent, index)) = stack.last_mut() else {
+                return;
+            };
+            match parent.child(*index) {
+                Some((key, child)) => {
+                    if *index > 0 {
+                        out.push(',');
+                    }
+                    if let Some(key) = key {
+                        write_string(out, key, &options);
+                        out.push(':');
+                    }
+                    *index += 1;
+                    next = Some(child);
+                }
+                None => {
+                    let object = matches!(parent.node(), Node::Object(_));
+                    out.push(if object { '}' } else { ']' });
+                    stack.pop();
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for NodeRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = String::new();
+        self.write_json(&mut out);
+        f.write_str(&out)
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.root().fmt(f)
+    }
+}
+
+/// Accumulates a Document while the parser walks the input
+pub(crate) struct DocumentBuilder<'a> {
+    input: &'a str,
+    duplicate_keys: DuplicateKeys,
+    keep_number_source: bool,
+    doc: Document,
+    /// Children of the containers currently open, innermost last
+    pending_elements: Vec<usize>,
+    /// Key range, key offset in the input, and value node id
+    pending_members: Vec<(Range<usize>, usize, usize)>,
+    /// Where each distinct key was stored in `text`, when interning keys
+    interned_keys: Option<HashMap<String, Range<usize>>>,
+}
+
+impl<'a> DocumentBuilder<'a> {
+    pub(crate) fn new(input: &'a str, options: &ParseOptions) -> Self {
+        Self {
+            input,
+            duplicate_keys: options.duplicate_keys,
+            keep_number_source: options.keep_number_source,
+            doc: Document::empty(),
+            pending_elements: Vec::new(),
+            pending_members: Vec::new(),
+            interned_keys: options.intern_keys.then(HashMap::new),
+        }
+    }
+
+    fn push(&mut self, node: Node) -> usize {
+        self.doc.nodes.push(node);
+        self.doc.nodes.len() - 1
+    }
+
+    fn push_text(&mut self, s: &str) -> Range<usize> {
+        let start = self.doc.text.len();
+        self.doc.text.push_str(s);
+        start..self.doc.text.len()
+    }
+
+    pub(crate) fn finish(self) -> Document {
+        self.doc
+    }
+
+    /// Fail on the first key in the members from `mark` that repeats an
+    /// earlier one
+    fn check_duplicates(&self, mark: usize) -> Result<(), ParseError> {
+        let mut seen = HashSet::new();
+        for (key, offset, _) in &self.pending_members[mark..] {
+            let key = &self.doc.text[key.clone()];
+            if !seen.insert(key) {
+                let message = format!("Duplicate key '{}' in object", key);
+                return Err(ParseError::new(message, self.input, *offset));
+            }
+        }
+        Ok(())
+    }
+
+    /// Merge the members from `mark` that share a key into one member, at
+    /// the first occurrence, holding an array of their values
+    fn collect_duplicates(&mut self, mark: usize) {
+        let mut groups: Vec<(Range<usize>, Vec<usize>)> = Vec::new();
+        {
+            let mut index: HashMap<&str, usize> = HashMap::new();
+            for (key, _, id) in &self.pending_members[mark..] {
+                match index.get(&self.doc.text[key.clone()]) {
+                    Some(&group) => groups[group].1.push(*id),
+                    None => {
+                        index.insert(&self.doc.text[key.clone()], groups.len());
+                        groups.push((key.clone(), vec![*id]));
+                    }
+                }
+            }
+        }
+
+        self.pending_members.truncate(mark);
+        for (key, ids) in groups {
+            let id = match ids[..] {
+                [id] => id,
+                _ => {
+                    let start = self.doc.elements.len();
+                    self.doc.elements.extend(ids);
+                    let range = start..self.doc.elements.len();
+                    self.push(Node::Array(range))
+                }
+            };
+            // Duplicates are resolved, so the key offset is no longer needed
+            self.pending_members.push((key, 0, id));
+        }
+    }
+}
+
+impl<'a> Builder<'a> for DocumentBuilder<'a> {
+    type Node = usize;
+    type Key = (Range<usize>, usize);
+
+    fn null(&mut self) -> usize {
+        self.push(Node::Null)
+    }
+
+    fn boolean(&mut self, b: bool) -> usize {
+        self.push(Node::Boolean(b))
+    }
+
+    fn number(&mut self, n: f64, source: &'a str) -> usize {
+        let source = (self.keep_number_source && n.is_finite()).then(|| self.push_text(source));
+        self.push(Node::Number(n, source))
+    }
+
+    fn string(&mut self, s: Cow<'a, str>) -> usize {
+        let range = self.push_text(&s);
+        self.push(Node::String(range))
+    }
+
+    fn start_array(&mut self) -> usize {
+        self.pending_elements.len()
+    }
+
+    fn push_element(&mut self, id: usize) {
+        self.pending_elements.push(id);
+    }
+
+    fn finish_array(&mut self, mark: usize) -> usize {
+        let start = self.doc.elements.len();
+        self.doc
+            .elements
+            .extend(self.pending_elements.drain(mark..));
+        let range = start..self.doc.elements.len();
+        self.push(Node::Array(range))
+    }
+
+    fn start_object(&mut self) -> usize {
+        self.pending_members.len()
+    }
+
+    /// Store a key ahead of its value, returning where it went. With
+    /// interning, a key seen before reuses the earlier copy.
+    fn key(&mut self, key: Cow<'a, str>, offset: usize) -> (Range<usize>, usize) {
+        if let Some(range) = self.interned_keys.as_ref().and_then(|keys| keys.get(&*key)) {
+            return (range.clone(), offset);
+        }
+
+        let range = self.push_text(&key);
+        if let Some(keys) = &mut self.interned_keys {
+            keys.insert(key.into_owned(), range.clone());
+        }
+        (range, offset)
+    }
+
+    fn push_member(&mut self, (key, offset): (Range<usize>, usize), id: usize) {
+        self.pending_members.push((key, offset, id));
+    }
+
+    fn finish_object(&mut self, mark: usize) -> Result<usize, ParseError> {
+        match self.duplicate_keys {
+            DuplicateKeys::LastWins => {}
+            DuplicateKeys::Error => self.check_duplicates(mark)?,
+            DuplicateKeys::Collect => self.collect_duplicates(mark),
+        }
+
+        let start = self.doc.members.len();
+        self.doc.members.extend(
+            self.pending_members
+                .drain(mark..)
+                .map(|(key, _, id)| (key, id)),
+        );
+        let range = start..self.doc.members.len();
+        Ok(self.push(Node::Object(range)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = r#"{
+        "name": "Alice",
+        "age": 30,
+        "tags": ["a", "b\nc"],
+        "address": {"city": "NYC", "zip": null},
+        "active": true
+    }"#;
+
+    #[test]
+    fn test_navigate() {
+        let doc = Document::parse(DOC).unwrap();
+        let root = doc.root();
+
+        assert_eq!(root.len(), 5);
+        assert_eq!(root.get("name").and_then(|n| n.as_str()), Some("Alice"));
+        assert_eq!(root.get("age").and_then(|n| n.as_f64()), Some(30.0));
+        assert_eq!(root.get("active").and_then(|n| n.as_bool()), Some(true));
+
+        let tags = root.get("tags").unwrap();
+        assert_eq!(tags.index(1).and_then(|n| n.as_str()), Some("b\nc"));
+        assert!(tags.index(2).is_none());
+        assert_eq!(
+            tags.elements()
+                .filter_map(|e| e.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b\nc"]
+        );
+
+        let address = root.get("address").unwrap();
+        assert!(address.get("zip").unwrap().is_null());
+        assert!(address.get("street").is_none());
+        assert!(root.get("name").unwrap().get("x").is_none());
+    }
+
+    #[test]
+    fn test_to_value_matches_value() {
+        let doc = Document::parse(DOC).unwrap();
+        assert_eq!(doc.to_value(), Value::from_str(DOC).unwrap());
+
+        let scalar = Document::parse("  -1.5 ").unwrap();
+        assert_eq!(scalar.root().as_f64(), Some(-1.5));
+        assert!(scalar.root().is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_keys_last_wins() {
+        let doc = Document::parse(r#"{"a": 1, "a": 2}"#).unwrap();
+        assert_eq!(doc.root().get("a").and_then(|n| n.as_f64()), Some(2.0));
+        assert_eq!(doc.root().members().count(), 2);
+        assert_eq!(doc.to_value(), Value::from_str(r#"{"a": 2}"#).unwrap());
+    }
+
+    #[test]
+    fn test_duplicate_keys_error() {
+        let options = ParseOptions {
+            duplicate_keys: DuplicateKeys::Error,
+            ..Default::default()
+        };
+        let input = r#"{"a": 1, "b": {"c": 2, "c": 3}}"#;
+        let err = Document::parse_with_options(input, &options).unwrap_err();
+        assert_eq!(err.message, "Duplicate key 'c' in object");
+        assert_eq!(
+            err,
+            Value::from_str_with_options(input, &options).unwrap_err()
+        );
+
+        let interned = ParseOptions {
+            intern_keys: true,
+            ..options.clone()
+        };
+        assert!(Document::parse_with_options(r#"{"a":1,"a":2}"#, &interned).is_err());
+        assert!(Document::parse_with_options(r#"[{"a":1},{"a":2}]"#, &options).is_ok());
+    }
+
+    #[test]
+    fn test_duplicate_keys_collect() {
+        let options = ParseOptions {
+            duplicate_keys: DuplicateKeys::Collect,
+            ..Default::default()
+        };
+        let input = r#"{"a": 1, "b": true, "a": {"x": [2]}, "a": "3", "c": {"d": 4, "d": 5}}"#;
+        let doc = Document::parse_with_options(input, &options).unwrap();
+        let root = doc.root();
+
+        // One member per key, at its first occurrence
+        let keys: Vec<&str> = root.members().map(|(key, _)| key).collect();
+        assert_eq!(keys, vec!["a", "b", "c"]);
+        let a = root.get("a").unwrap();
+        assert_eq!(a.len(), 3);
+        assert_eq!(a.index(2).and_then(|n| n.as_str()), Some("3"));
+        assert_eq!(root.get("b").and_then(|n| n.as_bool()), Some(true));
+
+        assert_eq!(
+            doc.to_value(),
+            Value::from_str_with_options(input, &options).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_intern_keys() {
+        let input = r#"[{"id": 1, "name": "a"}, {"id": 2, "name": "b"}, {"name": "id"}]"#;
+        let options = ParseOptions {
+            intern_keys: true,
+            ..Default::default()
+        };
+        let interned = Document::parse_with_options(input, &options).unwrap();
+        let plain = Document::parse(input).unwrap();
+
+        assert_eq!(interned.to_value(), plain.to_value());
+        assert_eq!(
+            interned
+                .root()
+                .index(2)
+                .and_then(|r| r.get("name"))
+                .and_then(|n| n.as_str()),
+            Some("id")
+        );
+        // The keys are stored once each; string values are not interned
+        assert_eq!(interned.text, "idnameabid");
+        assert_eq!(plain.text, "idnameaidnamebnameid");
+    }
+
+    #[test]
+    fn test_keep_number_source() {
+        let input = r#"{"price":1.10,"count":1e2,"items":[-0.50,{"id":7}],"name":"x\"y"}"#;
+        let options = ParseOptions {
+            keep_number_source: true,
+            ..Default::default()
+        };
+        let doc = Document::parse_with_options(input, &options).unwrap();
+        assert_eq!(doc.to_string(), input);
+
+        let root = doc.root();
+        let price = root.get("price").unwrap();
+        assert_eq!(price.number_source(), Some("1.10"));
+        assert_eq!(price.as_f64(), Some(1.1));
+        assert_eq!(price.to_string(), "1.10");
+        assert_eq!(root.get("name").unwrap().number_source(), None);
+
+        let plain = Document::parse(input).unwrap();
+        assert_eq!(plain.root().get("price").unwrap().number_source(), None);
+        assert_eq!(
+            plain.to_string(),
+            r#"{"price":1.1,"count":100,"items":[-0.5,{"id":7}],"name":"x\"y"}"#
+        );
+    }
+
+    #[test]
+    fn test_keep_number_source_non_finite() {
+        let options = ParseOptions {
+            keep_number_source: true,
+            ..ParseOptions::relaxed()
+        };
+        let doc = Document::parse_with_options("[NaN, -Infinity, 2.50]", &options).unwrap();
+        assert_eq!(doc.root().index(0).unwrap().number_source(), None);
+        assert_eq!(doc.to_string(), "[null,null,2.50]");
+    }
+
+    #[test]
+    fn test_deeply_nested() {
+        let depth = 200_000;
+        let input = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        let doc = Document::parse(&input).unwrap();
+        assert_eq!(doc.to_string(), input);
+
+        let value = doc.to_value();
+        let mut node = &value;
+        let mut levels = 0;
+        while let Value::Array(elements) = node {
+            levels += 1;
+            match elements.first() {
+                Some(element) => node = element,
+                None => break,
+            }
+        }
+        assert_eq!(levels, depth);
+        drop(value);
+
+        let input = format!("{}1{}", r#"{"a":"#.repeat(depth), "}".repeat(depth));
+        let doc = Document::parse(&input).unwrap();
+        assert_eq!(doc.to_string(), input);
+        assert!(matches!(doc.to_value(), Value::Object(_)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Document::parse(r#"{"a": [1, 2}"#).unwrap_err();
+        assert_eq!(err.message, "Expected ',' or ']' in array");
+    }
+}
@@ -0,0 +1,101 @@
+use crate::Value;
+
+impl Value {
+    /// Decode a string holding base64 (RFC 4648, standard alphabet) into
+    /// the bytes it encodes
+    ///
+    /// Padding is optional, but if present it must be correct. Whitespace,
+    /// the URL-safe alphabet and stray bits after the last byte are all
+    /// rejected. Returns `None` for anything that is not a string of valid
+    /// base64.
+    pub fn as_base64_bytes(&self) -> Option<Vec<u8>> {
+        decode(self.as_str()?.as_bytes())
+    }
+}
+
+fn decode(input: &[u8]) -> Option<Vec<u8>> {
+    let data = match input {
+        [data @ .., b'=', b'='] | [data @ .., b'='] => {
+            if !input.len().is_multiple_of(4) {
+                return None;
+            }
+            data
+        }
+        _ => input,
+    };
+    // A lone trailing character carries only 6 bits, never a whole byte
+    if data.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+    let mut bits: u32 = 0;
+    let mut bit_count = 0;
+    for &c in data {
+        bits = (bits << 6) | sextet(c)?;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            bytes.push((bits >> bit_count) as u8);
+            bits &= (1 << bit_count) - 1;
+        }
+    }
+
+    // Canonical encodings leave the unused low bits zero
+    (bits == 0).then_some(bytes)
+}
+
+fn sextet(c: u8) -> Option<u32> {
+    let value = match c {
+        b'A'..=b'Z' => c - b'A',
+        b'a'..=b'z' => c - b'a' + 26,
+        b'0'..=b'9' => c - b'0' + 52,
+        b'+' => 62,
+        b'/' => 63,
+        _ => return None,
+    };
+    Some(u32::from(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_str(s: &str) -> Option<Vec<u8>> {
+        Value::String(s.to_string()).as_base64_bytes()
+    }
+
+    #[test]
+    fn test_valid_base64() {
+        let cases: [(&str, &[u8]); 9] = [
+            ("", b""),
+            ("Zg==", b"f"),
+            ("Zm8=", b"fo"),
+            ("Zm9v", b"foo"),
+            ("Zm9vYg==", b"foob"),
+            ("Zm9vYmE=", b"fooba"),
+            ("Zm9vYmFy", b"foobar"),
+            ("Zm9vYg", b"foob"),
+            ("AP/+", &[0x00, 0xff, 0xfe]),
+        ];
+        for (encoded, expected) in cases {
+            assert_eq!(
+                decode_str(encoded).as_deref(),
+                Some(expected),
+                "{}",
+                encoded
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_base64() {
+        for encoded in [
+            "Z", "Zm9vY", "Zg=", "Zg===", "Z===", "Zm=9", "Zm9v\n", "Zm 9v", "AP_-", "Zh==", "Zm9=",
+        ] {
+            assert_eq!(decode_str(encoded), None, "{}", encoded);
+        }
+        assert_eq!(Value::Number(1.0).as_base64_bytes(), None);
+        assert_eq!(Value::Null.as_base64_bytes(), None);
+    }
+}
@@ -0,0 +1,142 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::parser::{Builder, Parser};
+use crate::{ParseError, Value};
+
+/// A JSON value that borrows its strings from the input it was parsed from
+///
+/// Strings without escape sequences are slices of the input; only strings
+/// that need unescaping allocate. Use this when the input outlives the parsed
+/// value and most of the document is read rather than kept.
+#[derive(Debug, PartialEq, Clone)]
+pub enum BorrowedValue<'a> {
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(Cow<'a, str>),
+    Array(Vec<BorrowedValue<'a>>),
+    Object(HashMap<Cow<'a, str>, BorrowedValue<'a>>),
+}
+
+impl<'a> BorrowedValue<'a> {
+    /// Parse a JSON string into a BorrowedValue that borrows from `input`
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(input: &'a str) -> Result<Self, ParseError> {
+        Parser::new(input).parse_borrowed()
+    }
+
+    /// Copy into an owned Value that no longer borrows from the input
+    pub fn to_owned(&self) -> Value {
+        match self {
+            BorrowedValue::Null => Value::Null,
+            BorrowedValue::Boolean(b) => Value::Boolean(*b),
+            BorrowedValue::Number(n) => Value::Number(*n),
+            BorrowedValue::String(s) => Value::String(s.to_string()),
+            BorrowedValue::Array(elements) => {
+                Value::Array(elements.iter().map(BorrowedValue::to_owned).collect())
+            }
+            BorrowedValue::Object(object) => Value::Object(
+                object
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_owned()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Builds a `BorrowedValue` tree, with the same scratch stacks as the
+/// `Value` builder. Repeated keys keep the last value.
+#[derive(Default)]
+pub(crate) struct BorrowedBuilder<'a> {
+    elements: Vec<BorrowedValue<'a>>,
+    members: Vec<(Cow<'a, str>, BorrowedValue<'a>)>,
+}
+
+impl<'a> Builder<'a> for BorrowedBuilder<'a> {
+    type Node = BorrowedValue<'a>;
+    type Key = Cow<'a, str>;
+
+    fn null(&mut self) -> BorrowedValue<'a> {
+        BorrowedValue::Null
+    }
+
+    fn boolean(&mut self, b: bool) -> BorrowedValue<'a> {
+        BorrowedValue::Boolean(b)
+    }
+
+    fn number(&mut self, n: f64, _source: &'a str) -> BorrowedValue<'a> {
+        BorrowedValue::Number(n)
+    }
+
+    fn string(&mut self, s: Cow<'a, str>) -> BorrowedValue<'a> {
+        BorrowedValue::String(s)
+    }
+
+    fn start_array(&mut self) -> usize {
+        self.elements.len()
+    }
+
+    fn push_element(&mut self, element: BorrowedValue<'a>) {
+        self.elements.push(element);
+    }
+
+    fn finish_array(&mut self, mark: usize) -> BorrowedValue<'a> {
+        BorrowedValue::Array(self.elements.drain(mark..).collect())
+    }
+
+    fn start_object(&mut self) -> usize {
+        self.members.len()
+    }
+
+    fn key(&mut self, key: Cow<'a, str>, _offset: usize) -> Cow<'a, str> {
+        key
+    }
+
+    fn push_member(&mut self, key: Cow<'a, str>, value: BorrowedValue<'a>) {
+        self.members.push((key, value));
+    }
+
+    fn finish_object(&mut self, mark: usize) -> Result<BorrowedValue<'a>, ParseError> {
+        Ok(BorrowedValue::Object(self.members.drain(mark..).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_borrows_plain_strings() {
+        let input = r#"{"name": "Alice", "tags": ["a", "b", "héllo"]}"#;
+        let value = BorrowedValue::from_str(input).unwrap();
+
+        if let BorrowedValue::Object(obj) = &value {
+            assert!(obj.keys().all(|k| matches!(k, Cow::Borrowed(_))));
+            assert!(matches!(
+                obj.get("name"),
+                Some(BorrowedValue::String(Cow::Borrowed("Alice")))
+            ));
+        } else {
+            panic!("Expected object");
+        }
+    }
+
+    #[test]
+    fn test_escaped_strings_are_owned() {
+        let value = BorrowedValue::from_str(r#""line\nbreak""#).unwrap();
+        assert_eq!(
+            value,
+            BorrowedValue::String(Cow::Owned("line\nbreak".to_string()))
+        );
+        assert!(matches!(value, BorrowedValue::String(Cow::Owned(_))));
+    }
+
+    #[test]
+    fn test_to_owned_matches_value() {
+        let input = r#"{"a": [1, true, null, "x\ty"], "b": {"c": -2.5}}"#;
+        let borrowed = BorrowedValue::from_str(input).unwrap();
+        assert_eq!(borrowed.to_owned(), Value::from_str(input).unwrap());
+    }
+}
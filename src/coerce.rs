@@ -0,0 +1,115 @@
+use crate::parser::Parser;
+use crate::Value;
+
+impl Value {
+    /// Read a number, accepting numeric strings from loosely-typed producers
+    ///
+    /// Returns the value of a `Number`, or of a `String` whose entire contents
+    /// are a valid JSON number (so `"42"` and `"-1.5e3"` are accepted, while
+    /// `"42abc"`, `" 42"` and `"0x10"` are not). Everything else is `None`.
+    pub fn coerce_number(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            Value::String(s) => Parser::new(s).parse_number_exact(),
+            _ => None,
+        }
+    }
+
+    /// Read a boolean, accepting the common encodings used in config files
+    ///
+    /// The accepted forms are:
+    ///
+    /// - `true` and `false`
+    /// - the strings `"true"`/`"false"` and `"yes"`/`"no"`, ignoring ASCII
+    ///   case, and `"1"`/`"0"`
+    /// - the numbers `1` and `0`
+    ///
+    /// Anything else, including other numbers, `"on"`/`"off"`, strings with
+    /// surrounding whitespace and `null`, is ambiguous and gives `None`.
+    pub fn coerce_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(b) => Some(*b),
+            Value::Number(n) if *n == 1.0 => Some(true),
+            Value::Number(n) if *n == 0.0 => Some(false),
+            Value::String(s) => {
+                if ["true", "yes", "1"]
+                    .iter()
+                    .any(|t| s.eq_ignore_ascii_case(t))
+                {
+                    Some(true)
+                } else if ["false", "no", "0"]
+                    .iter()
+                    .any(|f| s.eq_ignore_ascii_case(f))
+                {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coerce_number_from_number() {
+        assert_eq!(Value::Number(42.0).coerce_number(), Some(42.0));
+        assert_eq!(Value::Number(-0.5).coerce_number(), Some(-0.5));
+    }
+
+    #[test]
+    fn test_coerce_number_from_numeric_string() {
+        assert_eq!(Value::String("42".to_string()).coerce_number(), Some(42.0));
+        assert_eq!(
+            Value::String("-1.5e3".to_string()).coerce_number(),
+            Some(-1500.0)
+        );
+    }
+
+    #[test]
+    fn test_coerce_number_rejects_non_numbers() {
+        for s in ["42abc", "", " 42", "1.", "+1", "0x10", "abc"] {
+            assert_eq!(Value::String(s.to_string()).coerce_number(), None, "{s:?}");
+        }
+        assert_eq!(Value::Boolean(true).coerce_number(), None);
+        assert_eq!(Value::Null.coerce_number(), None);
+    }
+
+    #[test]
+    fn test_coerce_bool_accepted_forms() {
+        assert_eq!(Value::Boolean(true).coerce_bool(), Some(true));
+        assert_eq!(Value::Boolean(false).coerce_bool(), Some(false));
+        assert_eq!(Value::Number(1.0).coerce_bool(), Some(true));
+        assert_eq!(Value::Number(0.0).coerce_bool(), Some(false));
+
+        for s in ["true", "TRUE", "yes", "Yes", "1"] {
+            assert_eq!(
+                Value::String(s.to_string()).coerce_bool(),
+                Some(true),
+                "{s:?}"
+            );
+        }
+        for s in ["false", "False", "no", "NO", "0"] {
+            assert_eq!(
+                Value::String(s.to_string()).coerce_bool(),
+                Some(false),
+                "{s:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_coerce_bool_rejects_ambiguous_values() {
+        for s in ["", "on", "off", "y", " true", "2", "1.0"] {
+            assert_eq!(Value::String(s.to_string()).coerce_bool(), None, "{s:?}");
+        }
+        assert_eq!(Value::Number(2.0).coerce_bool(), None);
+        assert_eq!(Value::Number(0.5).coerce_bool(), None);
+        assert_eq!(Value::Null.coerce_bool(), None);
+        assert_eq!(Value::Array(vec![]).coerce_bool(), None);
+    }
+}
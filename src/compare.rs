@@ -0,0 +1,153 @@
+use std::cmp::Ordering;
+
+use crate::Value;
+
+impl Value {
+    /// Compare two values under a total order, so `sort_by(Value::compare)`
+    /// sorts even mixed arrays predictably
+    ///
+    /// Values of different types order by type: `Null` < booleans <
+    /// numbers < strings < arrays < objects. Within a type:
+    ///
+    /// - `false` < `true`.
+    /// - Numbers compare numerically, with `-0` equal to `0` as under `==`.
+    ///   `NaN` is greater than every other number and equal to itself.
+    /// - Strings compare by code point, which is also their UTF-8 byte
+    ///   order.
+    /// - Arrays compare element by element, the first difference deciding;
+    ///   an array that is a prefix of another is the smaller.
+    /// - Objects compare as their members sorted by key, as a list of
+    ///   `(key, value)` pairs: the first member whose key or value differs
+    ///   decides, and an object whose members are a prefix of another's is
+    ///   the smaller.
+    ///
+    /// Apart from `NaN`, `compare` gives `Equal` exactly when `==` holds.
+    pub fn compare(&self, other: &Value) -> Ordering {
+        match (self, other) {
+            (Value::Null, Value::Null) => Ordering::Equal,
+            (Value::Boolean(a), Value::Boolean(b)) => a.cmp(b),
+            (Value::Number(a), Value::Number(b)) => compare_numbers(*a, *b),
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            (Value::Array(a), Value::Array(b)) => {
+                for (a_element, b_element) in a.iter().zip(b) {
+                    let ordering = a_element.compare(b_element);
+                    if ordering != Ordering::Equal {
+                        return ordering;
+                    }
+                }
+                a.len().cmp(&b.len())
+            }
+            (Value::Object(_), Value::Object(_)) => {
+                let a_members = self.object_sorted_entries().unwrap_or_default();
+                let b_members = other.object_sorted_entries().unwrap_or_default();
+                for ((a_key, a_value), (b_key, b_value)) in a_members.iter().zip(&b_members) {
+                    let ordering = a_key.cmp(b_key).then_with(|| a_value.compare(b_value));
+                    if ordering != Ordering::Equal {
+                        return ordering;
+                    }
+                }
+                a_members.len().cmp(&b_members.len())
+            }
+            _ => type_rank(self).cmp(&type_rank(other)),
+        }
+    }
+}
+
+/// Position of a value's type in the cross-type order
+fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Boolean(_) => 1,
+        Value::Number(_) => 2,
+        Value::String(_) => 3,
+        Value::Array(_) => 4,
+        Value::Object(_) => 5,
+    }
+}
+
+/// Numeric order, with `NaN` above every other number
+fn compare_numbers(a: f64, b: f64) -> Ordering {
+    a.partial_cmp(&b)
+        .unwrap_or_else(|| a.is_nan().cmp(&b.is_nan()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Value {
+        Value::from_str(input).unwrap()
+    }
+
+    #[test]
+    fn test_sort_mixed_array() {
+        let mut values = parse(r#"[{"a": 1}, "b", [1], 2, null, true, "a", [], false, -1, {}]"#)
+            .into_array_iter()
+            .collect::<Vec<_>>();
+        values.sort_by(Value::compare);
+        assert_eq!(
+            Value::Array(values),
+            parse(r#"[null, false, true, -1, 2, "a", "b", [], [1], {}, {"a": 1}]"#)
+        );
+    }
+
+    #[test]
+    fn test_within_types() {
+        let ascending = [
+            ("false", "true"),
+            ("-2.5", "1"),
+            ("1", "1e3"),
+            (r#""""#, r#""a""#),
+            (r#""Z""#, r#""a""#),
+            (r#""ab""#, r#""b""#),
+            (r#""é""#, r#""日""#),
+            ("[1, 2]", "[1, 3]"),
+            ("[1, 2]", "[1, 2, 0]"),
+            ("[[1], 5]", "[[2]]"),
+            (r#"{"a": 1}"#, r#"{"a": 2}"#),
+            (r#"{"a": 9}"#, r#"{"b": 0}"#),
+            (r#"{"a": 1}"#, r#"{"a": 1, "b": 0}"#),
+            (r#"{"b": 0, "a": 1}"#, r#"{"a": 1, "c": 0}"#),
+        ];
+        for (smaller, larger) in ascending {
+            let (smaller, larger) = (parse(smaller), parse(larger));
+            assert_eq!(
+                smaller.compare(&larger),
+                Ordering::Less,
+                "{} < {}",
+                smaller,
+                larger
+            );
+            assert_eq!(larger.compare(&smaller), Ordering::Greater);
+        }
+
+        for input in [
+            "null",
+            "0",
+            r#""x""#,
+            "[1, [2]]",
+            r#"{"b": [1], "a": {"c": null}}"#,
+        ] {
+            let value = parse(input);
+            assert_eq!(value.compare(&value.clone()), Ordering::Equal, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_special_numbers() {
+        let zero = Value::Number(0.0);
+        assert_eq!(Value::Number(-0.0).compare(&zero), Ordering::Equal);
+
+        let nan = Value::Number(f64::NAN);
+        assert_eq!(nan.compare(&nan), Ordering::Equal);
+        assert_eq!(
+            nan.compare(&Value::Number(f64::INFINITY)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            Value::Number(f64::NEG_INFINITY).compare(&nan),
+            Ordering::Less
+        );
+        assert_eq!(nan.compare(&Value::String(String::new())), Ordering::Less);
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{FromJson, Value};
+
+/// Error returned when a `Value` cannot be converted to the requested type
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionError {
+    /// Short description of the mismatch, e.g. `"expected number, got
+    /// string"`, prefixed with the key where it occurred for maps
+    pub message: String,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+impl From<String> for ConversionError {
+    fn from(message: String) -> Self {
+        Self { message }
+    }
+}
+
+/// Scalars convert exactly as `FromJson` reads them
+macro_rules! impl_try_from_value {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<Value> for $t {
+                type Error = ConversionError;
+
+                fn try_from(value: Value) -> Result<Self, Self::Error> {
+                    Ok(<$t>::from_json(&value)?)
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_value!(bool, f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl TryFrom<Value> for String {
+    type Error = ConversionError;
+
+    fn try_from(mut value: Value) -> Result<Self, Self::Error> {
+        match &mut value {
+            Value::String(s) => Ok(std::mem::take(s)),
+            other => Err(mismatch("string", other)),
+        }
+    }
+}
+
+impl TryFrom<Value> for Vec<Value> {
+    type Error = ConversionError;
+
+    fn try_from(mut value: Value) -> Result<Self, Self::Error> {
+        match &mut value {
+            Value::Array(elements) => Ok(std::mem::take(elements)),
+            other => Err(mismatch("array", other)),
+        }
+    }
+}
+
+impl TryFrom<Value> for HashMap<String, Value> {
+    type Error = ConversionError;
+
+    fn try_from(mut value: Value) -> Result<Self, Self::Error> {
+        match &mut value {
+            Value::Object(object) => Ok(std::mem::take(object)),
+            other => Err(mismatch("object", other)),
+        }
+    }
+}
+
+fn mismatch(expected: &str, value: &Value) -> ConversionError {
+    format!("expected {}, got {}", expected, value.type_name()).into()
+}
+
+impl Value {
+    /// Convert an object whose values all have the same type into a typed
+    /// map, e.g. `{"a": 1, "b": 2}` into a `HashMap<String, f64>`
+    ///
+    /// Fails if `self` is not an object, or on the first member that does
+    /// not convert, with its key in the message (`at key 'b': expected
+    /// number, got string`). Members are visited in the map's iteration
+    /// order, so with several bad members any one of them may be reported.
+    pub fn try_into_map<T>(self) -> Result<HashMap<String, T>, ConversionError>
+    where
+        T: TryFrom<Value>,
+        T::Error: fmt::Display,
+    {
+        let object = HashMap::<String, Value>::try_from(self)?;
+        object
+            .into_iter()
+            .map(|(key, value)| match T::try_from(value) {
+                Ok(converted) => Ok((key, converted)),
+                Err(e) => Err(format!("at key '{}': {}", key, e).into()),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Value {
+        Value::from_str(input).unwrap()
+    }
+
+    #[test]
+    fn test_try_from_scalars() {
+        assert_eq!(bool::try_from(parse("true")), Ok(true));
+        assert_eq!(f64::try_from(parse("1.5")), Ok(1.5));
+        assert_eq!(u8::try_from(parse("255")), Ok(255));
+        assert_eq!(String::try_from(parse(r#""x""#)), Ok("x".to_string()));
+        assert_eq!(
+            Vec::<Value>::try_from(parse("[null]")),
+            Ok(vec![Value::Null])
+        );
+
+        assert_eq!(
+            bool::try_from(parse("1")).unwrap_err().message,
+            "expected boolean, got number"
+        );
+        assert_eq!(
+            u8::try_from(parse("256")).unwrap_err().to_string(),
+            "number 256 does not fit in u8"
+        );
+        assert_eq!(
+            String::try_from(parse("[]")).unwrap_err().message,
+            "expected string, got array"
+        );
+    }
+
+    #[test]
+    fn test_try_into_map() {
+        let map: HashMap<String, f64> = parse(r#"{"a": 1, "b": 2.5}"#).try_into_map().unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["a"], 1.0);
+        assert_eq!(map["b"], 2.5);
+
+        let names: HashMap<String, String> = parse(r#"{"x": "one"}"#).try_into_map().unwrap();
+        assert_eq!(names["x"], "one");
+        assert!(parse("{}").try_into_map::<bool>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_try_into_map_errors() {
+        let err = parse(r#"{"a": 1, "b": "two", "c": 3}"#)
+            .try_into_map::<f64>()
+            .unwrap_err();
+        assert_eq!(err.message, "at key 'b': expected number, got string");
+
+        let err = parse("[1, 2]").try_into_map::<f64>().unwrap_err();
+        assert_eq!(err.message, "expected object, got array");
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::BTreeSet;
+
+use crate::ser::write_number;
+use crate::Value;
+
+impl Value {
+    /// Write an array of flat objects as CSV, one row per object
+    ///
+    /// The header row is the union of every object's keys in sorted order.
+    /// A key an object lacks leaves its cell blank, and so does `null`.
+    /// Nested arrays and objects are written into their cell as compact
+    /// JSON, so no data is lost. Fields containing a comma, a quote or a
+    /// line break are quoted, with quotes doubled as in RFC 4180. Each row
+    /// ends with `\n`; an empty array gives an empty string.
+    ///
+    /// Fails with `"expected array, got ..."` if `self` is not an array, or
+    /// `"row N: expected object, got ..."` for the first element that is not
+    /// an object.
+    pub fn to_csv(&self) -> Result<String, String> {
+        let rows = self
+            .to_array_strict()?
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                row.to_object_strict()
+                    .map_err(|e| format!("row {}: {}", i, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let header: BTreeSet<&String> = rows.iter().flat_map(|row| row.keys()).collect();
+        if header.is_empty() {
+            return Ok(String::new());
+        }
+
+        let mut out = String::new();
+        write_row(&mut out, header.iter().map(|key| key.to_string()));
+        for row in rows {
+            write_row(
+                &mut out,
+                header
+                    .iter()
+                    .map(|key| row.get(*key).map(cell).unwrap_or_default()),
+            );
+        }
+        Ok(out)
+    }
+}
+
+/// The text of a field before CSV quoting
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Boolean(b) => b.to_string(),
+        Value::Number(n) => {
+            let mut out = String::new();
+            write_number(&mut out, *n);
+            out
+        }
+        Value::String(s) => s.clone(),
+        Value::Array(_) | Value::Object(_) => value.to_string(),
+    }
+}
+
+fn write_row(out: &mut String, fields: impl Iterator<Item = String>) {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(&field);
+        }
+    }
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records() {
+        let value = Value::from_str(
+            r#"[
+                {"name": "Alice", "age": 30, "active": true},
+                {"name": "Bob, Jr.", "email": "bob@example.com", "age": null},
+                {"name": "Carol \"CJ\"", "age": 41.5, "tags": ["a", "b"]}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value.to_csv().unwrap(),
+            "\
+active,age,email,name,tags
+true,30,,Alice,
+,,bob@example.com,\"Bob, Jr.\",
+,41.5,,\"Carol \"\"CJ\"\"\",\"[\"\"a\"\",\"\"b\"\"]\"
+"
+        );
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        assert_eq!(Value::from_str("[]").unwrap().to_csv().unwrap(), "");
+        assert_eq!(Value::from_str("[{}, {}]").unwrap().to_csv().unwrap(), "");
+        assert_eq!(
+            Value::from_str(r#"[{"note": "two\nlines"}]"#)
+                .unwrap()
+                .to_csv()
+                .unwrap(),
+            "note\n\"two\nlines\"\n"
+        );
+
+        assert_eq!(
+            Value::from_str(r#"{"a": 1}"#)
+                .unwrap()
+                .to_csv()
+                .unwrap_err(),
+            "expected array, got object"
+        );
+        assert_eq!(
+            Value::from_str(r#"[{"a": 1}, [2]]"#)
+                .unwrap()
+                .to_csv()
+                .unwrap_err(),
+            "row 1: expected object, got array"
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+
+use crate::pointer::{parse_pointer, push_token};
+use crate::Value;
+
+/// One difference between two documents, located by JSON Pointer
+#[derive(Debug, PartialEq)]
+pub(crate) enum Change<'a> {
+    Added(String, &'a Value),
+    Removed(String, &'a Value),
+    Changed(String, &'a Value, &'a Value),
+}
+
+/// Walk `old` and `new` side by side, collecting the changes that turn one
+/// into the other
+///
+/// Objects are compared member by member and arrays element by element, so
+/// a change deep inside a document is reported at its own path rather than
+/// as a change to the whole container. Values of different types, and
+/// unequal scalars, are a single `Changed`.
+pub(crate) fn diff<'a>(old: &'a Value, new: &'a Value) -> Vec<Change<'a>> {
+    let mut changes = Vec::new();
+    diff_at(old, new, &mut String::new(), &mut changes);
+    changes
+}
+
+fn diff_at<'a>(old: &'a Value, new: &'a Value, path: &mut String, changes: &mut Vec<Change<'a>>) {
+    match (old, new) {
+        (Value::Object(old_members), Value::Object(new_members)) => {
+            let mut keys: Vec<&String> = old_members.keys().chain(new_members.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                let len = path.len();
+                push_token(path, key);
+                match (old_members.get(key), new_members.get(key)) {
+                    (Some(old), Some(new)) => diff_at(old, new, path, changes),
+                    (Some(old), None) => changes.push(Change::Removed(path.clone(), old)),
+                    (None, Some(new)) => changes.push(Change::Added(path.clone(), new)),
+                    (None, None) => {}
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::Array(old_elements), Value::Array(new_elements)) => {
+            for i in 0..old_elements.len().max(new_elements.len()) {
+                let len = path.len();
+                push_token(path, &i.to_string());
+                match (old_elements.get(i), new_elements.get(i)) {
+                    (Some(old), Some(new)) => diff_at(old, new, path, changes),
+                    (Some(old), None) => changes.push(Change::Removed(path.clone(), old)),
+                    (None, Some(new)) => changes.push(Change::Added(path.clone(), new)),
+                    (None, None) => {}
+                }
+                path.truncate(len);
+            }
+        }
+        _ if old == new => {}
+        _ => changes.push(Change::Changed(path.clone(), old, new)),
+    }
+}
+
+impl Value {
+    /// Describe how `other` differs from `self`, one line per change, for
+    /// logs and command-line output
+    ///
+    /// Lines look like `changed /a/b from 1 to 2`, `added /c` and
+    /// `removed /d`, with paths written as JSON Pointers (the whole document
+    /// is `(root)`) and values as compact JSON. Object members are reported
+    /// in key order and array elements by index, so the summary is
+    /// deterministic. An empty summary means the documents are equal.
+    pub fn diff_summary(&self, other: &Value) -> Vec<String> {
+        diff(self, other)
+            .into_iter()
+            .map(|change| match change {
+                Change::Added(path, _) => format!("added {}", display_path(&path)),
+                Change::Removed(path, _) => format!("removed {}", display_path(&path)),
+                Change::Changed(path, old, new) => {
+                    format!("changed {} from {} to {}", display_path(&path), old, new)
+                }
+            })
+            .collect()
+    }
+
+    /// Build the smallest JSON merge patch (RFC 7386) that turns `self`
+    /// into `target` when applied with `apply_merge_patch`
+    ///
+    /// Members missing from `target` become `null`, added and changed
+    /// members carry their new value, and unchanged members are left out;
+    /// objects on both sides are diffed recursively. Arrays cannot be
+    /// patched piecewise, so a changed array is sent whole, and if either
+    /// side is not an object the patch is simply `target`. Merge patches
+    /// use `null` to mean "remove", so a `null` member in `target` cannot
+    /// be expressed: applying the patch deletes it instead.
+    pub fn merge_patch_diff(&self, target: &Value) -> Value {
+        let (Value::Object(source), Value::Object(wanted)) = (self, target) else {
+            return target.clone();
+        };
+
+        let mut patch = HashMap::new();
+        for key in source.keys() {
+            if !wanted.contains_key(key) {
+                patch.insert(key.clone(), Value::Null);
+            }
+        }
+        for (key, value) in wanted {
+            match source.get(key) {
+                Some(old) if old == value => {}
+                Some(old @ Value::Object(_)) if value.as_object().is_some() => {
+                    patch.insert(key.clone(), old.merge_patch_diff(value));
+                }
+                _ => {
+                    patch.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        Value::Object(patch)
+    }
+
+    /// Whether `self` and `other` are equal apart from the values at
+    /// `ignore_pointers`, for asserting on responses with volatile fields
+    /// such as IDs and timestamps
+    ///
+    /// An ignored path may differ in value or be present on one side only,
+    /// and everything beneath it is ignored too. Within arrays an index
+    /// token ignores just that element, while a `*` token matches any index
+    /// or key, so `/items/*/id` ignores the `id` of every item. A `*` token
+    /// is always the wildcard: a key literally named `*` cannot be ignored
+    /// on its own, since `/*` ignores every member of the root. Malformed
+    /// pointers are skipped; `""` ignores the whole document.
+    pub fn equals_ignoring(&self, other: &Value, ignore_pointers: &[&str]) -> bool {
+        let ignored: Vec<Vec<String>> = ignore_pointers
+            .iter()
+            .filter_map(|ptr| parse_pointer(ptr))
+            .collect();
+
+        diff(self, other).iter().all(|change| {
+            let path = match change {
+                Change::Added(path, _) | Change::Removed(path, _) | Change::Changed(path, _, _) => {
+                    path
+                }
+            };
+            let tokens = parse_pointer(path).unwrap_or_default();
+            ignored.iter().any(|pattern| covers(pattern, &tokens))
+        })
+    }
+}
+
+/// Whether the ignore `pattern` matches `path` or one of its ancestors
+fn covers(pattern: &[String], path: &[String]) -> bool {
+    pattern.len() <= path.len()
+        && pattern
+            .iter()
+            .zip(path)
+            .all(|(expected, token)| expected == "*" || expected == token)
+}
+
+fn display_path(path: &str) -> &str {
+    if path.is_empty() {
+        "(root)"
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Value {
+        Value::from_str(input).unwrap()
+    }
+
+    #[test]
+    fn test_diff_summary() {
+        let old = parse(r#"{"a": {"b": 1, "x": "same"}, "d": true, "list": [1, 2, 3], "t": 1}"#);
+        let new = parse(r#"{"a": {"b": 2, "x": "same"}, "c": null, "list": [1, 5], "t": "1"}"#);
+
+        assert_eq!(
+            old.diff_summary(&new),
+            vec![
+                "changed /a/b from 1 to 2",
+                "added /c",
+                "removed /d",
+                "changed /list/1 from 2 to 5",
+                "removed /list/2",
+                r#"changed /t from 1 to "1""#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_summary_equal_and_root() {
+        let value = parse(r#"{"a": [1, {"b": null}]}"#);
+        assert!(value.diff_summary(&value.clone()).is_empty());
+        assert_eq!(
+            parse("[1]").diff_summary(&parse(r#"{"k~/": 1}"#)),
+            vec![r#"changed (root) from [1] to {"k~/":1}"#]
+        );
+        assert_eq!(
+            parse(r#"{"a/b": 1}"#).diff_summary(&parse("{}")),
+            vec!["removed /a~1b"]
+        );
+    }
+
+    #[test]
+    fn test_merge_patch_diff() {
+        let source = parse(
+            r#"{"title": "Hello", "author": {"given": "John", "family": "Doe"}, "tags": ["a", "b"], "content": "x", "n": 1}"#,
+        );
+        let target = parse(
+            r#"{"title": "Hello!", "author": {"given": "John"}, "tags": ["a"], "content": "x", "n": 1, "phone": "555"}"#,
+        );
+
+        let patch = source.merge_patch_diff(&target);
+        assert_eq!(
+            patch,
+            parse(
+                r#"{"title": "Hello!", "author": {"family": null}, "tags": ["a"], "phone": "555"}"#
+            )
+        );
+
+        let mut patched = source.clone();
+        patched.apply_merge_patch(patch);
+        assert_eq!(patched, target);
+    }
+
+    #[test]
+    fn test_merge_patch_diff_round_trips() {
+        let cases = [
+            (r#"{"a": 1}"#, r#"{"a": 1}"#),
+            (r#"{"a": {"b": {"c": 1}}}"#, r#"{"a": {"b": {"d": [1]}}}"#),
+            (r#"{"a": {"b": 1}}"#, r#"{"a": 2}"#),
+            (r#"{"a": 2}"#, r#"{"a": {"b": 1}}"#),
+            (r#"[1, 2]"#, r#"{"a": 1}"#),
+            (r#"{"a": 1}"#, r#"[1]"#),
+            (r#""x""#, r#""y""#),
+        ];
+        for (source, target) in cases {
+            let (source, target) = (parse(source), parse(target));
+            let mut patched = source.clone();
+            patched.apply_merge_patch(source.merge_patch_diff(&target));
+            assert_eq!(patched, target, "{} -> {}", source, target);
+        }
+        assert_eq!(
+            parse(r#"{"a": [1]}"#).merge_patch_diff(&parse(r#"{"a": [1]}"#)),
+            parse("{}")
+        );
+    }
+
+    #[test]
+    fn test_equals_ignoring() {
+        let first =
+            parse(r#"{"id": 101, "name": "widget", "meta": {"created": "2024-01-01", "v": 2}}"#);
+        let second =
+            parse(r#"{"id": 202, "name": "widget", "meta": {"created": "2024-06-30", "v": 2}}"#);
+
+        assert!(first.equals_ignoring(&second, &["/id", "/meta/created"]));
+        assert!(first.equals_ignoring(&second, &["/id", "/meta"]));
+        assert!(!first.equals_ignoring(&second, &["/id"]));
+        assert!(!first.equals_ignoring(&second, &[]));
+        assert!(first.equals_ignoring(&second, &[""]));
+
+        // Present on one side only still counts as ignored
+        assert!(parse(r#"{"id": 1, "a": 2}"#).equals_ignoring(&parse(r#"{"a": 2}"#), &["/id"]));
+        assert!(!parse(r#"{"a": 1}"#).equals_ignoring(&parse(r#"{"a": 2}"#), &["a", "/b"]));
+    }
+
+    #[test]
+    fn test_equals_ignoring_arrays() {
+        let first = parse(r#"{"items": [{"id": 1, "n": "a"}, {"id": 2, "n": "b"}]}"#);
+        let second = parse(r#"{"items": [{"id": 7, "n": "a"}, {"id": 8, "n": "b"}]}"#);
+
+        assert!(first.equals_ignoring(&second, &["/items/*/id"]));
+        assert!(!first.equals_ignoring(&second, &["/items/0/id"]));
+        assert!(first.equals_ignoring(&second, &["/items/0/id", "/items/1/id"]));
+
+        let renamed = parse(r#"{"items": [{"id": 7, "n": "a"}, {"id": 8, "n": "c"}]}"#);
+        assert!(!first.equals_ignoring(&renamed, &["/items/*/id"]));
+        let longer = parse(r#"{"items": [{"id": 1, "n": "a"}, {"id": 2, "n": "b"}, {}]}"#);
+        assert!(!first.equals_ignoring(&longer, &["/items/*/id"]));
+        assert!(first.equals_ignoring(&longer, &["/items/2"]));
+    }
+
+    #[test]
+    fn test_equals_ignoring_star_key() {
+        let first = parse(r#"{"*": 1, "a": 2}"#);
+        let changed_star = parse(r#"{"*": 9, "a": 2}"#);
+        let changed_both = parse(r#"{"*": 9, "a": 3}"#);
+
+        assert!(first.equals_ignoring(&changed_star, &["/*"]));
+        // `*` is the wildcard even where a real `*` key exists
+        assert!(first.equals_ignoring(&changed_both, &["/*"]));
+        assert!(!first.equals_ignoring(&changed_both, &["/a"]));
+    }
+
+    #[test]
+    fn test_diff_changes() {
+        let old = parse(r#"{"a": 1}"#);
+        let new = parse(r#"{"a": 1, "b": [true]}"#);
+        assert_eq!(
+            diff(&old, &new),
+            vec![Change::Added("/b".to_string(), &parse("[true]"))]
+        );
+    }
+}
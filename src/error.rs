@@ -0,0 +1,168 @@
+use std::fmt;
+
+/// Broad category of a `ParseError`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// The text is not valid JSON
+    Syntax,
+    /// UTF-16 input contains an unpaired surrogate or an odd number of bytes
+    InvalidUtf16,
+    /// A number is longer than `ParseOptions::max_number_length`
+    NumberTooLong,
+    /// An object key is longer than `ParseOptions::max_key_length`
+    KeyTooLong,
+    /// A number is too large in magnitude to represent as an `f64`
+    NumberOutOfRange,
+    /// Streamed input is not valid UTF-8
+    InvalidUtf8,
+    /// Reading streamed input failed
+    Io,
+    /// The input ends partway through `null`, `true` or `false`
+    UnfinishedLiteral,
+}
+
+/// Error returned when the input is not valid JSON
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// Short description of the problem
+    pub message: String,
+    /// Byte offset into the input where the problem was found
+    pub offset: usize,
+    /// 1-based line of `offset`
+    pub line: usize,
+    /// 1-based column of `offset`, counted in characters
+    pub column: usize,
+}
+
+impl ParseError {
+    /// Create a syntax error at `offset`, working out its line and column in
+    /// `input`
+    pub(crate) fn new(message: impl Into<String>, input: &str, offset: usize) -> Self {
+        Self::with_kind(ParseErrorKind::Syntax, message, input, offset)
+    }
+
+    pub(crate) fn with_kind(
+        kind: ParseErrorKind,
+        message: impl Into<String>,
+        input: &str,
+        offset: usize,
+    ) -> Self {
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Self {
+            kind,
+            message: message.into(),
+            offset,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// Render the error with the offending line of `input` and a caret under
+    /// the column, in the style of compiler diagnostics:
+    ///
+    /// ```text
+    /// error: Expected ':' after object key
+    ///  --> line 1, column 6
+    ///   |
+    /// 1 | {"a" 1}
+    ///   |      ^
+    /// ```
+    ///
+    /// `input` must be the text that produced the error. A hand-built error
+    /// with `line` or `column` 0 is rendered as if it were 1.
+    pub fn render(&self, input: &str) -> String {
+        let source_line = input.lines().nth(self.line.saturating_sub(1)).unwrap_or("");
+        let line_number = self.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+
+        // Keep tabs so the caret lines up with the source line
+        let padding: String = source_line
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        format!(
+            "error: {}\n{}--> line {}, column {}\n{} |\n{} | {}\n{} | {}^",
+            self.message,
+            gutter,
+            self.line,
+            self.column,
+            gutter,
+            line_number,
+            source_line,
+            gutter,
+            padding
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.line, self.column
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ParseError, ParseErrorKind, Value};
+
+    #[test]
+    fn test_error_position() {
+        let err = Value::from_str("{\n  \"a\": 1,\n  \"b\" 2\n}").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::Syntax);
+        assert_eq!(err.message, "Expected ':' after object key");
+        assert_eq!(err.offset, 18);
+        assert_eq!((err.line, err.column), (3, 7));
+        assert_eq!(
+            err.to_string(),
+            "Expected ':' after object key at line 3, column 7"
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let input = "{\n  \"a\": 1,\n  \"b\" 2\n}";
+        let err = Value::from_str(input).unwrap_err();
+        assert_eq!(
+            err.render(input),
+            "error: Expected ':' after object key\n \
+             --> line 3, column 7\n  \
+             |\n\
+             3 |   \"b\" 2\n  \
+             |       ^"
+        );
+    }
+
+    #[test]
+    fn test_render_keeps_tabs_aligned() {
+        let input = "[\t1 2]";
+        let err = Value::from_str(input).unwrap_err();
+        assert!(err.render(input).ends_with("1 | [\t1 2]\n  |  \t  ^"));
+    }
+
+    #[test]
+    fn test_render_hand_built_error() {
+        let err = ParseError {
+            kind: ParseErrorKind::Syntax,
+            message: "Bad input".to_string(),
+            offset: 0,
+            line: 0,
+            column: 0,
+        };
+        assert_eq!(
+            err.render("[1]"),
+            "error: Bad input\n --> line 0, column 0\n  |\n0 | [1]\n  | ^"
+        );
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+
+use crate::Value;
+
+/// Types that can be built from a parsed JSON value
+///
+/// Implement this for your own types to extract them from a `Value` with
+/// `Value::deserialize`. Errors are short descriptions such as
+/// `"expected string, got number"`, prefixed with the index or key where
+/// the mismatch occurred inside arrays and objects.
+pub trait FromJson: Sized {
+    fn from_json(value: &Value) -> Result<Self, String>;
+}
+
+impl Value {
+    /// Build a typed value from this JSON value
+    pub fn deserialize<T: FromJson>(&self) -> Result<T, String> {
+        T::from_json(self)
+    }
+
+    /// Read a boolean, or fail with `"expected boolean, got ..."`
+    ///
+    /// The `*_strict` readers are the `Result` counterparts of the `as_*`
+    /// accessors, for call sites that propagate a wrong type with `?`. They
+    /// never coerce.
+    pub fn to_bool_strict(&self) -> Result<bool, String> {
+        self.as_bool().ok_or_else(|| mismatch("boolean", self))
+    }
+
+    /// Read a number, or fail with `"expected number, got ..."`
+    pub fn to_f64_strict(&self) -> Result<f64, String> {
+        self.as_f64().ok_or_else(|| mismatch("number", self))
+    }
+
+    /// Read a string, or fail with `"expected string, got ..."`
+    pub fn to_str_strict(&self) -> Result<&str, String> {
+        self.as_str().ok_or_else(|| mismatch("string", self))
+    }
+
+    /// Read an array's elements, or fail with `"expected array, got ..."`
+    pub fn to_array_strict(&self) -> Result<&Vec<Value>, String> {
+        self.as_array().ok_or_else(|| mismatch("array", self))
+    }
+
+    /// Read an object's members, or fail with `"expected object, got ..."`
+    pub fn to_object_strict(&self) -> Result<&HashMap<String, Value>, String> {
+        self.as_object().ok_or_else(|| mismatch("object", self))
+    }
+}
+
+fn mismatch(expected: &str, value: &Value) -> String {
+    format!("expected {}, got {}", expected, value.type_name())
+}
+
+impl FromJson for Value {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        Ok(value.clone())
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Boolean(b) => Ok(*b),
+            _ => Err(mismatch("boolean", value)),
+        }
+    }
+}
+
+impl FromJson for f64 {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Number(n) => Ok(*n),
+            _ => Err(mismatch("number", value)),
+        }
+    }
+}
+
+impl FromJson for f32 {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        f64::from_json(value).map(|n| n as f32)
+    }
+}
+
+/// Integers accept numbers that are whole and within `[min, end)`
+macro_rules! impl_from_json_int {
+    ($($t:ty => $min:expr, $end:expr;)*) => {
+        $(
+            impl FromJson for $t {
+                fn from_json(value: &Value) -> Result<Self, String> {
+                    let n = f64::from_json(value)?;
+                    if n.fract() != 0.0 || n < $min || n >= $end {
+                        return Err(format!("number {} does not fit in {}", n, stringify!($t)));
+                    }
+                    Ok(n as $t)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_json_int! {
+    i8 => i8::MIN as f64, -(i8::MIN as f64);
+    i16 => i16::MIN as f64, -(i16::MIN as f64);
+    i32 => i32::MIN as f64, -(i32::MIN as f64);
+    i64 => i64::MIN as f64, -(i64::MIN as f64);
+    isize => isize::MIN as f64, -(isize::MIN as f64);
+    u8 => 0.0, u8::MAX as f64 + 1.0;
+    u16 => 0.0, u16::MAX as f64 + 1.0;
+    u32 => 0.0, u32::MAX as f64 + 1.0;
+    u64 => 0.0, u64::MAX as f64 + 1.0;
+    usize => 0.0, usize::MAX as f64 + 1.0;
+}
+
+impl FromJson for String {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::String(s) => Ok(s.clone()),
+            _ => Err(mismatch("string", value)),
+        }
+    }
+}
+
+/// `null` becomes `None`; anything else must convert to `T`
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Null => Ok(None),
+            _ => T::from_json(value).map(Some),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Array(elements) => elements
+                .iter()
+                .enumerate()
+                .map(|(i, element)| {
+                    T::from_json(element).map_err(|e| format!("at index {}: {}", i, e))
+                })
+                .collect(),
+            _ => Err(mismatch("array", value)),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for HashMap<String, T> {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        match value {
+            Value::Object(object) => object
+                .iter()
+                .map(|(key, v)| {
+                    T::from_json(v)
+                        .map(|t| (key.clone(), t))
+                        .map_err(|e| format!("at key '{}': {}", key, e))
+                })
+                .collect(),
+            _ => Err(mismatch("object", value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct User {
+        name: String,
+        age: u32,
+        tags: Vec<String>,
+        email: Option<String>,
+        scores: HashMap<String, f64>,
+    }
+
+    impl FromJson for User {
+        fn from_json(value: &Value) -> Result<Self, String> {
+            let Value::Object(object) = value else {
+                return Err(mismatch("object", value));
+            };
+            static NULL: Value = Value::Null;
+            let field = |key: &str| object.get(key).unwrap_or(&NULL);
+
+            Ok(User {
+                name: field("name").deserialize()?,
+                age: field("age").deserialize()?,
+                tags: field("tags").deserialize()?,
+                email: field("email").deserialize()?,
+                scores: field("scores").deserialize()?,
+            })
+        }
+    }
+
+    #[test]
+    fn test_deserialize_struct() {
+        let value = Value::from_str(
+            r#"{"name": "Alice", "age": 30, "tags": ["admin", "dev"], "scores": {"math": 9.5}}"#,
+        )
+        .unwrap();
+        let user: User = value.deserialize().unwrap();
+
+        assert_eq!(
+            user,
+            User {
+                name: "Alice".to_string(),
+                age: 30,
+                tags: vec!["admin".to_string(), "dev".to_string()],
+                email: None,
+                scores: HashMap::from([("math".to_string(), 9.5)]),
+            }
+        );
+    }
+
+    #[test]
+    fn test_deserialize_errors() {
+        let value = Value::from_str(r#"{"name": "Bob", "age": 30, "tags": ["a", 1]}"#).unwrap();
+        assert_eq!(
+            value.deserialize::<User>().unwrap_err(),
+            "at index 1: expected string, got number"
+        );
+
+        assert_eq!(
+            Value::Null.deserialize::<bool>().unwrap_err(),
+            "expected boolean, got null"
+        );
+        assert_eq!(
+            Value::Number(1.5).deserialize::<i32>().unwrap_err(),
+            "number 1.5 does not fit in i32"
+        );
+        assert!(Value::Number(-1.0).deserialize::<u8>().is_err());
+        assert!(Value::Number(256.0).deserialize::<u8>().is_err());
+        assert!(Value::Number(9223372036854775808.0)
+            .deserialize::<i64>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_deserialize_primitives() {
+        assert_eq!(Value::Number(-128.0).deserialize::<i8>(), Ok(-128));
+        assert_eq!(Value::Number(255.0).deserialize::<u8>(), Ok(255));
+        assert_eq!(Value::Boolean(true).deserialize::<bool>(), Ok(true));
+        assert_eq!(Value::Number(2.5).deserialize::<f32>(), Ok(2.5));
+        assert_eq!(Value::Number(3.0).deserialize::<Option<u64>>(), Ok(Some(3)));
+    }
+
+    #[test]
+    fn test_strict_readers() {
+        let value = Value::from_str(r#"{"b": true, "n": 2, "s": "x", "a": [1], "o": {}}"#).unwrap();
+        let object = value.to_object_strict().unwrap();
+        assert_eq!(object["b"].to_bool_strict(), Ok(true));
+        assert_eq!(object["n"].to_f64_strict(), Ok(2.0));
+        assert_eq!(object["s"].to_str_strict(), Ok("x"));
+        assert_eq!(object["a"].to_array_strict(), Ok(&vec![Value::Number(1.0)]));
+        assert!(object["o"].to_object_strict().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_strict_reader_errors() {
+        let number = Value::Number(1.0);
+        assert_eq!(
+            number.to_bool_strict().unwrap_err(),
+            "expected boolean, got number"
+        );
+        assert_eq!(
+            Value::String("1".to_string()).to_f64_strict().unwrap_err(),
+            "expected number, got string"
+        );
+        assert_eq!(
+            Value::Null.to_str_strict().unwrap_err(),
+            "expected string, got null"
+        );
+        assert_eq!(
+            Value::from_str("{}")
+                .unwrap()
+                .to_array_strict()
+                .unwrap_err(),
+            "expected array, got object"
+        );
+        assert_eq!(
+            Value::from_str("[]")
+                .unwrap()
+                .to_object_strict()
+                .unwrap_err(),
+            "expected object, got array"
+        );
+    }
+}
@@ -1,6 +1,50 @@
 use std::collections::HashMap;
+use std::str::FromStr;
+
+mod arena;
+mod base64;
+mod borrowed;
+mod coerce;
+mod compare;
+mod convert;
+mod csv;
+mod diff;
+mod error;
+mod from_json;
+mod merge;
+mod parser;
+mod pointer;
+mod query;
+mod random;
+mod schema;
+mod ser;
+mod stream;
+mod transform;
+mod traverse;
+mod utf16;
+mod yaml;
+
+pub use arena::{Document, NodeRef};
+pub use borrowed::BorrowedValue;
+pub use convert::ConversionError;
+pub use error::{ParseError, ParseErrorKind};
+pub use from_json::FromJson;
+pub use merge::TypeConflict;
+pub use parser::{unescape_json_string, DuplicateKeys, ParseOptions};
+pub use pointer::{extract_pointer, Segment};
+pub use ser::{
+    escape_json_string, escape_json_string_into, LineEnding, SerializeChunks, SerializeOptions,
+};
+pub use stream::parse_stream;
+pub use transform::NormalizeOptions;
+
+use parser::Parser;
 
 /// The main JSON value type representing any valid JSON value
+///
+/// Parsing, serializing and dropping walk nested values with a heap stack,
+/// so they handle any depth. `Clone`, `==`, `Debug` and the traversal and
+/// transformation methods still recurse, one call per level of nesting.
 #[derive(Debug, PartialEq, Clone)]
 pub enum Value {
     Null,
@@ -11,289 +55,381 @@ pub enum Value {
     Object(HashMap<String, Value>),
 }
 
+static NULL: Value = Value::Null;
+
+/// Drops nested containers from a heap stack instead of recursively, so
+/// dropping a deeply nested value cannot overflow the call stack
+impl Drop for Value {
+    fn drop(&mut self) {
+        let mut children = match self {
+            Value::Array(elements) if !elements.is_empty() => std::mem::take(elements),
+            Value::Object(object) if !object.is_empty() => {
+                object.drain().map(|(_, value)| value).collect()
+            }
+            _ => return,
+        };
+        while let Some(mut child) = children.pop() {
+            match &mut child {
+                Value::Array(elements) => children.append(elements),
+                Value::Object(object) => children.extend(object.drain().map(|(_, value)| value)),
+                _ => {}
+            }
+            // `child` is now childless, so dropping it here does not recurse
+        }
+    }
+}
+
 impl Value {
     /// Parse a JSON string into a Value
-    pub fn from_str(input: &str) -> Result<Self, String> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(input: &str) -> Result<Self, ParseError> {
         Parser::new(input).parse()
     }
-}
 
-/// Simple JSON parser
-struct Parser {
-    chars: Vec<char>,
-    pos: usize,
-}
+    /// Parse a JSON string into a Value using the given options
+    pub fn from_str_with_options(input: &str, options: &ParseOptions) -> Result<Self, ParseError> {
+        Parser::with_options(input, options.clone()).parse()
+    }
 
-impl Parser {
-    fn new(input: &str) -> Self {
-        Self {
-            chars: input.chars().collect(),
-            pos: 0,
-        }
+    /// Parse forgiving, JSON5-style input, with every option of
+    /// `ParseOptions::relaxed` turned on
+    ///
+    /// Accepts comments, trailing commas, single-quoted strings, unquoted
+    /// keys, and `NaN`/`Infinity`, for hand-written or sloppy documents.
+    pub fn parse_relaxed(input: &str) -> Result<Self, ParseError> {
+        Value::from_str_with_options(input, &ParseOptions::relaxed())
     }
 
-    fn parse(&mut self) -> Result<Value, String> {
-        self.skip_whitespace();
-        let value = self.parse_value()?;
-        self.skip_whitespace();
-        
-        if self.pos < self.chars.len() {
-            return Err(format!("Unexpected characters after JSON value"));
-        }
-        
-        Ok(value)
+    /// Build an array value from its elements
+    pub fn array_of(elements: Vec<Value>) -> Value {
+        Value::Array(elements)
     }
 
-    fn parse_value(&mut self) -> Result<Value, String> {
-        self.skip_whitespace();
-        
-        if self.pos >= self.chars.len() {
-            return Err("Unexpected end of input".to_string());
-        }
+    /// Build an object value from key/value pairs. If a key repeats, the last
+    /// pair wins, as when parsing.
+    pub fn object_of(pairs: impl IntoIterator<Item = (String, Value)>) -> Value {
+        Value::Object(pairs.into_iter().collect())
+    }
 
-        match self.chars[self.pos] {
-            'n' => self.parse_null(),
-            't' | 'f' => self.parse_boolean(),
-            '"' => self.parse_string(),
-            '[' => self.parse_array(),
-            '{' => self.parse_object(),
-            '-' | '0'..='9' => self.parse_number(),
-            c => Err(format!("Unexpected character: '{}'", c)),
-        }
+    /// An empty array with room for `capacity` elements, for building a
+    /// value whose size is known up front without reallocating
+    pub fn array_with_capacity(capacity: usize) -> Value {
+        Value::Array(Vec::with_capacity(capacity))
     }
 
-    fn parse_null(&mut self) -> Result<Value, String> {
-        if self.consume_literal("null") {
-            Ok(Value::Null)
-        } else {
-            Err("Invalid null literal".to_string())
+    /// An empty object with room for `capacity` members
+    pub fn object_with_capacity(capacity: usize) -> Value {
+        Value::Object(HashMap::with_capacity(capacity))
+    }
+
+    /// Consume an array, yielding its elements in order
+    ///
+    /// Any other variant yields nothing.
+    pub fn into_array_iter(mut self) -> std::vec::IntoIter<Value> {
+        match &mut self {
+            Value::Array(elements) => std::mem::take(elements).into_iter(),
+            _ => Vec::new().into_iter(),
         }
     }
 
-    fn parse_boolean(&mut self) -> Result<Value, String> {
-        if self.consume_literal("true") {
-            Ok(Value::Boolean(true))
-        } else if self.consume_literal("false") {
-            Ok(Value::Boolean(false))
-        } else {
-            Err("Invalid boolean literal".to_string())
+    /// Consume an object, yielding its `(key, value)` members in the map's
+    /// iteration order
+    ///
+    /// Any other variant yields nothing.
+    pub fn into_object_iter(mut self) -> std::collections::hash_map::IntoIter<String, Value> {
+        match &mut self {
+            Value::Object(object) => std::mem::take(object).into_iter(),
+            _ => HashMap::new().into_iter(),
         }
     }
 
-    fn parse_number(&mut self) -> Result<Value, String> {
-        let start = self.pos;
-        
-        // Optional minus
-        if self.peek() == Some('-') {
-            self.pos += 1;
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// `None` for `Null`, otherwise `Some(self)`, for when an explicit
+    /// `null` means the same as an absent value
+    pub fn into_option(self) -> Option<Value> {
+        match self {
+            Value::Null => None,
+            value => Some(value),
         }
+    }
 
-        // Integer part
-        if self.peek() == Some('0') {
-            self.pos += 1;
-        } else if self.peek().map_or(false, |c| c.is_ascii_digit()) {
-            while self.peek().map_or(false, |c| c.is_ascii_digit()) {
-                self.pos += 1;
-            }
-        } else {
-            return Err("Invalid number".to_string());
+    /// Borrowing form of `into_option`, for chains such as
+    /// `object.get("x").and_then(Value::as_option)`
+    pub fn as_option(&self) -> Option<&Value> {
+        match self {
+            Value::Null => None,
+            value => Some(value),
         }
+    }
 
-        // Optional fractional part
-        if self.peek() == Some('.') {
-            self.pos += 1;
-            if !self.peek().map_or(false, |c| c.is_ascii_digit()) {
-                return Err("Invalid number: decimal point must be followed by digit".to_string());
-            }
-            while self.peek().map_or(false, |c| c.is_ascii_digit()) {
-                self.pos += 1;
-            }
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(b) => Some(*b),
+            _ => None,
         }
+    }
 
-        // Optional exponent
-        if self.peek() == Some('e') || self.peek() == Some('E') {
-            self.pos += 1;
-            if self.peek() == Some('+') || self.peek() == Some('-') {
-                self.pos += 1;
-            }
-            if !self.peek().map_or(false, |c| c.is_ascii_digit()) {
-                return Err("Invalid number: exponent must have digits".to_string());
-            }
-            while self.peek().map_or(false, |c| c.is_ascii_digit()) {
-                self.pos += 1;
-            }
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
         }
+    }
 
-        let num_str: String = self.chars[start..self.pos].iter().collect();
-        num_str
-            .parse::<f64>()
-            .map(Value::Number)
-            .map_err(|_| "Failed to parse number".to_string())
-    }
-
-    fn parse_string(&mut self) -> Result<Value, String> {
-        self.pos += 1; // consume opening quote
-        let mut result = String::new();
-
-        while self.pos < self.chars.len() {
-            match self.chars[self.pos] {
-                '"' => {
-                    self.pos += 1;
-                    return Ok(Value::String(result));
-                }
-                '\\' => {
-                    self.pos += 1;
-                    if self.pos >= self.chars.len() {
-                        return Err("Unterminated string escape".to_string());
-                    }
-                    match self.chars[self.pos] {
-                        '"' => result.push('"'),
-                        '\\' => result.push('\\'),
-                        '/' => result.push('/'),
-                        'b' => result.push('\u{0008}'),
-                        'f' => result.push('\u{000C}'),
-                        'n' => result.push('\n'),
-                        'r' => result.push('\r'),
-                        't' => result.push('\t'),
-                        'u' => {
-                            self.pos += 1;
-                            if self.pos + 4 > self.chars.len() {
-                                return Err("Invalid unicode escape".to_string());
-                            }
-                            let hex: String = self.chars[self.pos..self.pos + 4].iter().collect();
-                            let code = u32::from_str_radix(&hex, 16)
-                                .map_err(|_| "Invalid unicode escape")?;
-                            let ch = char::from_u32(code)
-                                .ok_or_else(|| "Invalid unicode code point")?;
-                            result.push(ch);
-                            self.pos += 3; // Will be incremented by 1 at end of loop
-                        }
-                        c => return Err(format!("Invalid escape sequence: \\{}", c)),
-                    }
-                    self.pos += 1;
-                }
-                c => {
-                    result.push(c);
-                    self.pos += 1;
-                }
-            }
+    /// Whether this is a number with no fractional part, such as `42.0`
+    ///
+    /// Numbers are stored as `f64`, so `42` and `42.0` parse to the same
+    /// value and both count; either way the value serializes as `42`.
+    /// Non-finite numbers are not integers.
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Value::Number(n) if n.fract() == 0.0)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
         }
+    }
 
-        Err("Unterminated string".to_string())
+    /// Length of a string in characters (Unicode scalar values); `None` for
+    /// non-strings
+    ///
+    /// This is the measure `max_string_length` uses. Byte limits such as
+    /// `ParseOptions::max_key_length` count UTF-8 bytes instead; see
+    /// `string_byte_len`.
+    pub fn string_len(&self) -> Option<usize> {
+        Some(self.as_str()?.chars().count())
     }
 
-    fn parse_array(&mut self) -> Result<Value, String> {
-        self.pos += 1; // consume '['
-        let mut elements = Vec::new();
+    /// Length of a string in UTF-8 bytes; `None` for non-strings
+    pub fn string_byte_len(&self) -> Option<usize> {
+        Some(self.as_str()?.len())
+    }
+
+    /// Map a string to the variant paired with it in `mapping`, such as
+    /// `&[("red", Color::Red), ("green", Color::Green)]`
+    ///
+    /// Names are matched exactly. Returns `None` for strings not in
+    /// `mapping` and for non-strings.
+    pub fn as_enum<T: Clone>(&self, mapping: &[(&str, T)]) -> Option<T> {
+        let s = self.as_str()?;
+        mapping
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|(_, variant)| variant.clone())
+    }
 
-        self.skip_whitespace();
-        
-        // Empty array
-        if self.peek() == Some(']') {
-            self.pos += 1;
-            return Ok(Value::Array(elements));
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        match self {
+            Value::Array(elements) => Some(elements),
+            _ => None,
         }
+    }
 
-        loop {
-            elements.push(self.parse_value()?);
-            self.skip_whitespace();
-
-            match self.peek() {
-                Some(',') => {
-                    self.pos += 1;
-                    self.skip_whitespace();
-                }
-                Some(']') => {
-                    self.pos += 1;
-                    return Ok(Value::Array(elements));
-                }
-                _ => return Err("Expected ',' or ']' in array".to_string()),
-            }
+    pub fn as_object(&self) -> Option<&HashMap<String, Value>> {
+        match self {
+            Value::Object(object) => Some(object),
+            _ => None,
         }
     }
 
-    fn parse_object(&mut self) -> Result<Value, String> {
-        self.pos += 1; // consume '{'
-        let mut object = HashMap::new();
+    /// Keys of an object, in the map's iteration order; `None` for
+    /// non-objects
+    pub fn object_keys(&self) -> Option<Vec<&String>> {
+        Some(self.as_object()?.keys().collect())
+    }
 
-        self.skip_whitespace();
+    /// Values of an object, in the same order as `object_keys`; `None` for
+    /// non-objects
+    pub fn object_values(&self) -> Option<Vec<&Value>> {
+        Some(self.as_object()?.values().collect())
+    }
 
-        // Empty object
-        if self.peek() == Some('}') {
-            self.pos += 1;
-            return Ok(Value::Object(object));
-        }
+    /// Entries of an object sorted by key, for stable display and logging;
+    /// `None` for non-objects
+    pub fn object_sorted_entries(&self) -> Option<Vec<(&String, &Value)>> {
+        let mut entries: Vec<(&String, &Value)> = self.as_object()?.iter().collect();
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        Some(entries)
+    }
 
-        loop {
-            self.skip_whitespace();
+    /// The first candidate that is not `Null`, for "use X, else Y, else a
+    /// default" fallbacks
+    ///
+    /// If every candidate is `Null`, or there are none, the result is a
+    /// shared `Null`.
+    pub fn coalesce<'a>(candidates: &[&'a Value]) -> &'a Value {
+        candidates
+            .iter()
+            .copied()
+            .find(|candidate| !candidate.is_null())
+            .unwrap_or(&NULL)
+    }
 
-            // Parse key (must be a string)
-            if self.peek() != Some('"') {
-                return Err("Expected string key in object".to_string());
-            }
+    /// First element of an array; `None` for empty arrays and non-arrays
+    pub fn first(&self) -> Option<&Value> {
+        self.as_array()?.first()
+    }
+
+    /// Last element of an array; `None` for empty arrays and non-arrays
+    pub fn last(&self) -> Option<&Value> {
+        self.as_array()?.last()
+    }
 
-            let key = match self.parse_string()? {
-                Value::String(s) => s,
-                _ => unreachable!(),
-            };
+    /// Read an array of numbers; `None` unless `self` is an array whose
+    /// elements are all numbers
+    pub fn as_f64_array(&self) -> Option<Vec<f64>> {
+        self.as_array()?.iter().map(Value::as_f64).collect()
+    }
 
-            self.skip_whitespace();
+    /// Read an array of strings; `None` unless `self` is an array whose
+    /// elements are all strings
+    pub fn as_str_array(&self) -> Option<Vec<&str>> {
+        self.as_array()?.iter().map(Value::as_str).collect()
+    }
 
-            // Expect colon
-            if self.peek() != Some(':') {
-                return Err("Expected ':' after object key".to_string());
-            }
-            self.pos += 1;
-
-            // Parse value
-            let value = self.parse_value()?;
-            object.insert(key, value);
-
-            self.skip_whitespace();
-
-            match self.peek() {
-                Some(',') => {
-                    self.pos += 1;
-                    self.skip_whitespace();
-                }
-                Some('}') => {
-                    self.pos += 1;
-                    return Ok(Value::Object(object));
-                }
-                _ => return Err("Expected ',' or '}' in object".to_string()),
-            }
+    /// Read an array of objects, the usual shape of a list of records;
+    /// `None` unless `self` is an array whose elements are all objects
+    pub fn as_array_of_objects(&self) -> Option<Vec<&HashMap<String, Value>>> {
+        self.as_array()?.iter().map(Value::as_object).collect()
+    }
+
+    /// The JSON type shared by every element of an array, such as
+    /// `"number"` or `"string"`, for checking data before `as_f64_array` or
+    /// `as_str_array`
+    ///
+    /// Type names are the ones used in error messages: `"null"`,
+    /// `"boolean"`, `"number"`, `"string"`, `"array"` and `"object"`. An
+    /// empty array has no element types and gives `Some("empty")`. Mixed
+    /// arrays and non-arrays give `None`.
+    pub fn is_homogeneous_array(&self) -> Option<&'static str> {
+        let mut elements = self.as_array()?.iter();
+        let Some(first) = elements.next() else {
+            return Some("empty");
+        };
+        let type_name = first.type_name();
+        elements
+            .all(|element| element.type_name() == type_name)
+            .then_some(type_name)
+    }
+
+    /// Split an array's elements into those matching `predicate` and the
+    /// rest, keeping their order; `None` for non-arrays
+    pub fn partition_array(
+        &self,
+        predicate: impl Fn(&Value) -> bool,
+    ) -> Option<(Vec<Value>, Vec<Value>)> {
+        Some(self.as_array()?.iter().cloned().partition(|v| predicate(v)))
+    }
+
+    /// Split an array into arrays of at most `size` elements, in order
+    ///
+    /// The last chunk holds whatever is left over. A `size` of 0 is treated
+    /// as unlimited, giving the whole array as a single chunk. An empty array
+    /// gives no chunks, and non-arrays give `None`.
+    pub fn chunk_array(&self, size: usize) -> Option<Vec<Value>> {
+        let elements = self.as_array()?;
+        let size = if size == 0 {
+            elements.len().max(1)
+        } else {
+            size
+        };
+        Some(
+            elements
+                .chunks(size)
+                .map(|chunk| Value::Array(chunk.to_vec()))
+                .collect(),
+        )
+    }
+
+    /// Borrow the elements of an array in `[start, end)`, for paging
+    /// through it; `None` for non-arrays
+    ///
+    /// `end` is clamped to the array's length, and a `start` at or past
+    /// `end` gives an empty window.
+    pub fn array_slice(&self, start: usize, end: usize) -> Option<Vec<&Value>> {
+        let elements = self.as_array()?;
+        let end = end.min(elements.len());
+        Some(elements[start.min(end)..end].iter().collect())
+    }
+
+    /// Build a new array by applying `f` to each element in order; `None`
+    /// for non-arrays
+    pub fn array_map(&self, f: impl Fn(&Value) -> Value) -> Option<Value> {
+        Some(Value::Array(self.as_array()?.iter().map(f).collect()))
+    }
+
+    /// Borrow the elements of an array for editing in place
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self {
+            Value::Array(elements) => Some(elements),
+            _ => None,
         }
     }
 
-    fn skip_whitespace(&mut self) {
-        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
-            self.pos += 1;
+    /// Borrow the members of an object for editing in place
+    pub fn as_object_mut(&mut self) -> Option<&mut HashMap<String, Value>> {
+        match self {
+            Value::Object(object) => Some(object),
+            _ => None,
         }
     }
 
-    fn peek(&self) -> Option<char> {
-        if self.pos < self.chars.len() {
-            Some(self.chars[self.pos])
-        } else {
-            None
+    /// Borrow the members of an object for editing, first turning `Null`
+    /// into an empty object
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is neither an object nor `Null`; existing data is
+    /// never silently discarded.
+    pub fn ensure_object(&mut self) -> &mut HashMap<String, Value> {
+        if self.is_null() {
+            *self = Value::Object(HashMap::new());
+        }
+        match self {
+            Value::Object(object) => object,
+            other => panic!("ensure_object called on {}", other.type_name()),
         }
     }
 
-    fn consume_literal(&mut self, literal: &str) -> bool {
-        let chars: Vec<char> = literal.chars().collect();
-        
-        if self.pos + chars.len() > self.chars.len() {
-            return false;
+    /// Borrow the elements of an array for editing, first turning `Null`
+    /// into an empty array
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is neither an array nor `Null`.
+    pub fn ensure_array(&mut self) -> &mut Vec<Value> {
+        if self.is_null() {
+            *self = Value::Array(Vec::new());
+        }
+        match self {
+            Value::Array(elements) => elements,
+            other => panic!("ensure_array called on {}", other.type_name()),
         }
+    }
 
-        for (i, &ch) in chars.iter().enumerate() {
-            if self.chars[self.pos + i] != ch {
-                return false;
-            }
+    /// Name of the JSON type of this value, for error messages
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Boolean(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
         }
+    }
+}
+
+impl FromStr for Value {
+    type Err = ParseError;
 
-        self.pos += chars.len();
-        true
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Value::from_str(input)
     }
 }
 
@@ -313,6 +449,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_number() {
         assert_eq!(Value::from_str("42").unwrap(), Value::Number(42.0));
         assert_eq!(Value::from_str("-17").unwrap(), Value::Number(-17.0));
@@ -321,6 +458,114 @@ mod tests {
         assert_eq!(Value::from_str("2.5e-3").unwrap(), Value::Number(2.5e-3));
     }
 
+    #[test]
+    fn test_leading_zeros() {
+        for (input, offset) in [("0123", 1), ("00", 1), ("-01", 2), ("[1, 007]", 5)] {
+            let err = Value::from_str(input).unwrap_err();
+            assert_eq!(err.kind, ParseErrorKind::Syntax);
+            assert_eq!(err.message, "Invalid number: leading zero", "{}", input);
+            assert_eq!(err.offset, offset, "{}", input);
+        }
+
+        assert_eq!(Value::from_str("0").unwrap(), Value::Number(0.0));
+        assert_eq!(Value::from_str("0.5").unwrap(), Value::Number(0.5));
+        assert_eq!(Value::from_str("-0.05").unwrap(), Value::Number(-0.05));
+        assert_eq!(Value::from_str("0e3").unwrap(), Value::Number(0.0));
+    }
+
+    #[test]
+    fn test_long_numbers() {
+        let digits = "9".repeat(10_000);
+
+        let options = ParseOptions {
+            max_number_length: Some(1000),
+            ..Default::default()
+        };
+        let err = Value::from_str_with_options(&format!("[1, {}]", digits), &options).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::NumberTooLong);
+        assert_eq!(
+            err.message,
+            "Number too long: 10000 characters, limit is 1000"
+        );
+        assert_eq!(err.offset, 4);
+        assert_eq!(
+            Value::from_str_with_options("-12.5e3", &options).unwrap(),
+            Value::Number(-12500.0)
+        );
+
+        // Without a limit the digits are read, but the result overflows
+        let err = Value::from_str(&digits).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::NumberOutOfRange);
+        assert_eq!(err.offset, 0);
+    }
+
+    #[test]
+    fn test_max_key_length() {
+        let options = ParseOptions {
+            max_key_length: Some(8),
+            ..Default::default()
+        };
+        let long_key = "k".repeat(100);
+        let input = format!(r#"{{"short": 1, "{}": 2}}"#, long_key);
+
+        let err = Value::from_str_with_options(&input, &options).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::KeyTooLong);
+        assert_eq!(err.message, "Key too long: 100 bytes, limit is 8");
+        assert_eq!(err.offset, 13);
+        assert!(Value::from_str(&input).is_ok());
+
+        // The limit applies to keys only, and to the unescaped key
+        assert!(Value::from_str_with_options(r#"{"a": "a long string value"}"#, &options).is_ok());
+        assert!(Value::from_str_with_options(r#"{"\u0041\u0042": 1}"#, &options).is_ok());
+        let err = Document::parse_with_options(&input, &options).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::KeyTooLong);
+    }
+
+    #[test]
+    fn test_number_out_of_range() {
+        let err = Value::from_str(r#"{"n": 1e400}"#).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::NumberOutOfRange);
+        assert_eq!(err.message, "Number out of range");
+        assert_eq!(err.offset, 6);
+        assert!(Value::from_str("-1e400").is_err());
+
+        // Underflow rounds to zero rather than failing
+        assert_eq!(Value::from_str("1e-400").unwrap(), Value::Number(0.0));
+        assert_eq!(
+            Value::from_str("1.7976931348623157e308").unwrap(),
+            Value::Number(f64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_unfinished_literals() {
+        let err = Value::from_str("nul").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnfinishedLiteral);
+        assert_eq!(
+            err.message,
+            "Unfinished literal 'null': input ends after 'nul'"
+        );
+        assert_eq!(err.offset, 0);
+
+        let err = Value::from_str(r#"{"a": [1, fals"#).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnfinishedLiteral);
+        assert_eq!(
+            err.message,
+            "Unfinished literal 'false': input ends after 'fals'"
+        );
+        assert_eq!((err.offset, err.line, err.column), (10, 1, 11));
+
+        // Something other than the literal is still a syntax error
+        let err = Value::from_str("[nul]").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::Syntax);
+        assert_eq!(err.message, "Invalid null literal");
+        assert_eq!(err.offset, 1);
+        assert_eq!(
+            Value::from_str("tru ").unwrap_err().kind,
+            ParseErrorKind::Syntax
+        );
+    }
+
     #[test]
     fn test_string() {
         assert_eq!(
@@ -333,6 +578,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_unicode_escapes() {
+        assert_eq!(
+            Value::from_str(r#""caf\u00e9 \u4E2D""#).unwrap(),
+            Value::String("café 中".to_string())
+        );
+        assert_eq!(
+            Value::from_str(r#""\uD83D\uDE00!""#).unwrap(),
+            Value::String("😀!".to_string())
+        );
+
+        let err = Value::from_str(r#""\uD83D""#).unwrap_err();
+        assert_eq!(err.message, "Invalid unicode code point");
+        assert_eq!(err.offset, 3);
+        let err = Value::from_str(r#""\uD83D\u0041""#).unwrap_err();
+        assert_eq!(err.message, "Invalid unicode code point");
+        let err = Value::from_str(r#""\uDE00\uD83D""#).unwrap_err();
+        assert_eq!(err.message, "Invalid unicode code point");
+        let err = Value::from_str(r#""\u+123""#).unwrap_err();
+        assert_eq!(err.message, "Invalid unicode escape");
+        let err = Value::from_str(r#""\u12""#).unwrap_err();
+        assert_eq!(err.message, "Invalid unicode escape");
+    }
+
+    #[test]
+    fn test_unescape_json_string() {
+        let cases = [
+            (r#"plain"#, "plain"),
+            (r#"\"\\\/"#, "\"\\/"),
+            (r"\b\f\n\r\t", "\u{8}\u{c}\n\r\t"),
+            (r"caf\u00e9 \u65E5", "café 日"),
+            (r"\ud83d\ude00!", "😀!"),
+            (r#"bare " quote"#, "bare \" quote"),
+            ("", ""),
+        ];
+        for (escaped, expected) in cases {
+            assert_eq!(
+                unescape_json_string(escaped).unwrap(),
+                expected,
+                "{}",
+                escaped
+            );
+        }
+
+        for (escaped, message, offset) in [
+            (r"a\x", "Invalid escape sequence: \\x", 2),
+            (r"\'", "Invalid escape sequence: \\'", 1),
+            (r"\u12", "Invalid unicode escape", 2),
+            (r"ab\", "Unterminated string escape", 3),
+            (r"x\ud83d", "Invalid unicode code point", 3),
+        ] {
+            let err = unescape_json_string(escaped).unwrap_err();
+            assert_eq!(
+                (err.message.as_str(), err.offset),
+                (message, offset),
+                "{}",
+                escaped
+            );
+        }
+    }
+
     #[test]
     fn test_array() {
         assert_eq!(Value::from_str("[]").unwrap(), Value::Array(vec![]));
@@ -367,10 +673,10 @@ mod tests {
                 "zip": "10001"
             }
         }"#;
-        
+
         let result = Value::from_str(json).unwrap();
-        
-        if let Value::Object(obj) = result {
+
+        if let Value::Object(obj) = &result {
             assert_eq!(obj.get("name"), Some(&Value::String("Alice".to_string())));
             assert_eq!(obj.get("age"), Some(&Value::Number(30.0)));
             assert_eq!(obj.get("active"), Some(&Value::Boolean(true)));
@@ -378,4 +684,753 @@ mod tests {
             panic!("Expected object");
         }
     }
+
+    #[test]
+    fn test_nested_containers_share_scratch() {
+        let result = Value::from_str(r#"[[1, 2], {"a": [3, {"b": 4}], "c": 5}, [], 6]"#).unwrap();
+        let mut inner = HashMap::new();
+        inner.insert("b".to_string(), Value::Number(4.0));
+        let mut object = HashMap::new();
+        object.insert(
+            "a".to_string(),
+            Value::Array(vec![Value::Number(3.0), Value::Object(inner)]),
+        );
+        object.insert("c".to_string(), Value::Number(5.0));
+        assert_eq!(
+            result,
+            Value::Array(vec![
+                Value::Array(vec![Value::Number(1.0), Value::Number(2.0)]),
+                Value::Object(object),
+                Value::Array(vec![]),
+                Value::Number(6.0),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_deeply_nested_array() {
+        let depth = 10_000;
+        let input = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        let value = Value::from_str(&input).unwrap();
+
+        let mut levels = 0;
+        let mut node = &value;
+        while let Some(elements) = node.as_array() {
+            levels += 1;
+            node = elements.first().unwrap_or(&NULL);
+        }
+        assert_eq!(levels, depth);
+        drop(value);
+
+        let err = Value::from_str(&"[".repeat(depth)).unwrap_err();
+        assert_eq!(err.message, "Unexpected end of input");
+        assert_eq!(err.offset, depth);
+        assert_eq!(
+            extract_pointer(&format!("[{}, 7]", input), "/1").unwrap(),
+            Some(Value::Number(7.0))
+        );
+    }
+
+    #[test]
+    fn test_drop_deep_value_on_small_stack() {
+        let depth = 100_000;
+        std::thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(move || {
+                let arrays = format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+                drop(Value::from_str(&arrays).unwrap());
+
+                let objects = format!("{}null{}", r#"{"a":["#.repeat(depth), "]}".repeat(depth));
+                drop(Value::from_str(&objects).unwrap());
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_array_of() {
+        assert_eq!(Value::array_of(vec![]), Value::Array(vec![]));
+        assert_eq!(
+            Value::array_of(vec![Value::Null, Value::Boolean(true)]),
+            Value::from_str("[null, true]").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_object_of() {
+        let value = Value::object_of([
+            ("a".to_string(), Value::Number(1.0)),
+            ("b".to_string(), Value::array_of(vec![Value::Null])),
+            ("a".to_string(), Value::Number(2.0)),
+        ]);
+        assert_eq!(value, Value::from_str(r#"{"a": 2, "b": [null]}"#).unwrap());
+        assert_eq!(Value::object_of([]), Value::Object(HashMap::new()));
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        let mut array = Value::array_with_capacity(16);
+        let elements = array.as_array_mut().unwrap();
+        assert!(elements.is_empty());
+        assert!(elements.capacity() >= 16);
+        let ptr = elements.as_ptr();
+        elements.extend((0..16).map(|i| Value::Number(i as f64)));
+        assert_eq!(elements.as_ptr(), ptr);
+
+        let object = Value::object_with_capacity(16);
+        let members = object.as_object().unwrap();
+        assert!(members.is_empty());
+        assert!(members.capacity() >= 16);
+        assert_eq!(object, Value::from_str("{}").unwrap());
+    }
+
+    #[test]
+    fn test_into_array_iter() {
+        let value = Value::from_str(r#"[1, "two", null]"#).unwrap();
+        let elements: Vec<Value> = value.into_array_iter().collect();
+        assert_eq!(
+            elements,
+            vec![
+                Value::Number(1.0),
+                Value::String("two".to_string()),
+                Value::Null
+            ]
+        );
+        assert_eq!(Value::Number(1.0).into_array_iter().count(), 0);
+        assert_eq!(Value::from_str("{}").unwrap().into_array_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_into_object_iter() {
+        let value = Value::from_str(r#"{"a": 1, "b": [true]}"#).unwrap();
+        let mut members: Vec<(String, Value)> = value.into_object_iter().collect();
+        members.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            members,
+            vec![
+                ("a".to_string(), Value::Number(1.0)),
+                ("b".to_string(), Value::Array(vec![Value::Boolean(true)])),
+            ]
+        );
+        assert_eq!(Value::Null.into_object_iter().count(), 0);
+        assert_eq!(
+            Value::from_str("[1]").unwrap().into_object_iter().count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_accessors() {
+        let value = Value::from_str(r#"{"a": [1, "x", true, null]}"#).unwrap();
+        let elements = value.as_object().unwrap()["a"].as_array().unwrap();
+        assert_eq!(elements[0].as_f64(), Some(1.0));
+        assert_eq!(elements[1].as_str(), Some("x"));
+        assert_eq!(elements[2].as_bool(), Some(true));
+        assert!(elements[3].is_null());
+
+        assert_eq!(elements[0].as_str(), None);
+        assert_eq!(elements[1].as_f64(), None);
+        assert!(value.as_array().is_none());
+        assert!(elements[0].as_object().is_none());
+    }
+
+    #[test]
+    fn test_is_integer() {
+        for (n, text) in [(42.0, "42"), (-7.0, "-7"), (0.0, "0"), (1e21, "1e21")] {
+            assert!(Value::Number(n).is_integer(), "{}", n);
+            assert_eq!(Value::Number(n).to_string(), text);
+        }
+        assert!(Value::from_str("42.0").unwrap().is_integer());
+
+        for n in [2.75, -0.5, f64::NAN, f64::INFINITY] {
+            assert!(!Value::Number(n).is_integer(), "{}", n);
+        }
+        assert_eq!(Value::Number(2.75).to_string(), "2.75");
+        assert!(!Value::String("42".to_string()).is_integer());
+    }
+
+    #[test]
+    fn test_string_lengths() {
+        let ascii = Value::String("hello".to_string());
+        assert_eq!(ascii.string_len(), Some(5));
+        assert_eq!(ascii.string_byte_len(), Some(5));
+
+        // é is 2 bytes, 日 is 3 and 😀 is 4
+        let multibyte = Value::from_str(r#""é日😀""#).unwrap();
+        assert_eq!(multibyte.string_len(), Some(3));
+        assert_eq!(multibyte.string_byte_len(), Some(9));
+
+        assert_eq!(Value::String(String::new()).string_len(), Some(0));
+        assert_eq!(Value::Number(12.0).string_len(), None);
+        assert_eq!(Value::Null.string_byte_len(), None);
+    }
+
+    #[test]
+    fn test_as_enum() {
+        #[derive(Clone, Debug, PartialEq)]
+        enum Color {
+            Red,
+            Green,
+            Blue,
+        }
+        let mapping = [
+            ("red", Color::Red),
+            ("green", Color::Green),
+            ("blue", Color::Blue),
+        ];
+
+        let value = Value::from_str(r#"["green", "blue", "red", "purple", "Red", 1]"#).unwrap();
+        let colors: Vec<Option<Color>> = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_enum(&mapping))
+            .collect();
+        assert_eq!(
+            colors,
+            vec![
+                Some(Color::Green),
+                Some(Color::Blue),
+                Some(Color::Red),
+                None,
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_object_keys_and_values() {
+        let value = Value::from_str(r#"{"a": 1, "b": [true], "c": null}"#).unwrap();
+        let keys = value.object_keys().unwrap();
+        let values = value.object_values().unwrap();
+
+        // Both follow the map's order, so they pair up
+        let object = value.as_object().unwrap();
+        for (key, v) in keys.iter().zip(&values) {
+            assert_eq!(&object[*key], *v);
+        }
+
+        let mut keys = keys;
+        keys.sort();
+        assert_eq!(keys, vec!["a", "b", "c"]);
+        assert_eq!(values.len(), 3);
+
+        assert_eq!(Value::from_str("{}").unwrap().object_keys(), Some(vec![]));
+        assert_eq!(Value::from_str("[1]").unwrap().object_keys(), None);
+        assert_eq!(Value::Null.object_values(), None);
+    }
+
+    #[test]
+    fn test_object_sorted_entries() {
+        let value =
+            Value::from_str(r#"{"zeta": 1, "alpha": [true], "Mid": null, "beta": 2}"#).unwrap();
+        let entries = value.object_sorted_entries().unwrap();
+        let keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["Mid", "alpha", "beta", "zeta"]);
+        assert_eq!(entries[3].1, &Value::Number(1.0));
+
+        assert_eq!(
+            Value::from_str("{}").unwrap().object_sorted_entries(),
+            Some(vec![])
+        );
+        assert_eq!(
+            Value::from_str("[1]").unwrap().object_sorted_entries(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_into_option() {
+        assert_eq!(Value::Null.into_option(), None);
+        assert_eq!(Value::Null.as_option(), None);
+        for value in [
+            Value::Number(0.0),
+            Value::Boolean(false),
+            Value::String(String::new()),
+            Value::Array(vec![Value::Null]),
+        ] {
+            assert_eq!(value.as_option(), Some(&value));
+            assert_eq!(value.clone().into_option(), Some(value));
+        }
+
+        let value = Value::from_str(r#"{"x": null, "y": 2}"#).unwrap();
+        let object = value.as_object().unwrap();
+        assert_eq!(object.get("x").and_then(Value::as_option), None);
+        assert_eq!(object.get("missing").and_then(Value::as_option), None);
+        assert_eq!(
+            object.get("y").and_then(Value::as_option),
+            Some(&Value::Number(2.0))
+        );
+    }
+
+    #[test]
+    fn test_coalesce() {
+        let config = Value::from_str(r#"{"port": null, "fallback": 8080, "zero": 0}"#).unwrap();
+        let object = config.as_object().unwrap();
+        let default = Value::Number(80.0);
+
+        assert_eq!(
+            Value::coalesce(&[&object["port"], &object["fallback"], &default]),
+            &Value::Number(8080.0)
+        );
+        assert_eq!(
+            Value::coalesce(&[&object["zero"], &default]),
+            &Value::Number(0.0)
+        );
+        assert_eq!(
+            Value::coalesce(&[&object["port"], &Value::Null]),
+            &Value::Null
+        );
+        assert_eq!(Value::coalesce(&[]), &Value::Null);
+    }
+
+    #[test]
+    fn test_first_and_last() {
+        let value = Value::from_str(r#"[1, "two", null]"#).unwrap();
+        assert_eq!(value.first(), Some(&Value::Number(1.0)));
+        assert_eq!(value.last(), Some(&Value::Null));
+
+        let empty = Value::Array(vec![]);
+        assert_eq!(empty.first(), None);
+        assert_eq!(empty.last(), None);
+
+        let object = Value::from_str(r#"{"a": 1}"#).unwrap();
+        assert_eq!(object.first(), None);
+        assert_eq!(Value::String("ab".to_string()).last(), None);
+    }
+
+    #[test]
+    fn test_typed_array_extractors() {
+        let numbers = Value::from_str("[1, 2.5, -3]").unwrap();
+        assert_eq!(numbers.as_f64_array(), Some(vec![1.0, 2.5, -3.0]));
+        assert_eq!(numbers.as_str_array(), None);
+
+        let strings = Value::from_str(r#"["a", "b"]"#).unwrap();
+        assert_eq!(strings.as_str_array(), Some(vec!["a", "b"]));
+        assert_eq!(strings.as_f64_array(), None);
+
+        let mixed = Value::from_str(r#"[1, "a"]"#).unwrap();
+        assert_eq!(mixed.as_f64_array(), None);
+        assert_eq!(mixed.as_str_array(), None);
+
+        assert_eq!(Value::Array(vec![]).as_f64_array(), Some(vec![]));
+        assert_eq!(Value::Number(1.0).as_f64_array(), None);
+    }
+
+    #[test]
+    fn test_partition_array() {
+        let value = Value::from_str("[1, 2, 3, 4, 5, 6, 7]").unwrap();
+        let (evens, odds) = value
+            .partition_array(|v| v.as_f64().is_some_and(|n| n % 2.0 == 0.0))
+            .unwrap();
+        assert_eq!(
+            evens,
+            Value::from_str("[2, 4, 6]")
+                .unwrap()
+                .into_array_iter()
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            odds,
+            Value::from_str("[1, 3, 5, 7]")
+                .unwrap()
+                .into_array_iter()
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            Value::Array(vec![]).partition_array(|_| true),
+            Some((vec![], vec![]))
+        );
+        assert_eq!(Value::Null.partition_array(|_| true), None);
+    }
+
+    #[test]
+    fn test_chunk_array() {
+        let value = Value::from_str("[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]").unwrap();
+        let chunks = value.chunk_array(3).unwrap();
+        assert_eq!(
+            chunks,
+            vec![
+                Value::from_str("[1, 2, 3]").unwrap(),
+                Value::from_str("[4, 5, 6]").unwrap(),
+                Value::from_str("[7, 8, 9]").unwrap(),
+                Value::from_str("[10]").unwrap(),
+            ]
+        );
+
+        assert_eq!(value.chunk_array(0), Some(vec![value.clone()]));
+        assert_eq!(value.chunk_array(20), Some(vec![value.clone()]));
+        assert_eq!(Value::Array(vec![]).chunk_array(3), Some(vec![]));
+        assert_eq!(Value::Array(vec![]).chunk_array(0), Some(vec![]));
+        assert_eq!(Value::Null.chunk_array(3), None);
+    }
+
+    #[test]
+    fn test_as_array_of_objects() {
+        let value = Value::from_str(r#"[{"id": 1}, {"id": 2, "name": "b"}, {}]"#).unwrap();
+        let records = value.as_array_of_objects().unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1]["name"], Value::String("b".to_string()));
+        assert!(records[2].is_empty());
+
+        assert_eq!(
+            Value::from_str("[]").unwrap().as_array_of_objects(),
+            Some(vec![])
+        );
+        assert_eq!(
+            Value::from_str(r#"[{"id": 1}, [2]]"#)
+                .unwrap()
+                .as_array_of_objects(),
+            None
+        );
+        assert_eq!(
+            Value::from_str(r#"{"id": 1}"#)
+                .unwrap()
+                .as_array_of_objects(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_homogeneous_array() {
+        let cases = [
+            ("[1, 2.5, -3]", Some("number")),
+            (r#"["a", "b"]"#, Some("string")),
+            (r#"[{"a": 1}, {}]"#, Some("object")),
+            ("[[1], [\"x\"]]", Some("array")),
+            ("[null]", Some("null")),
+            ("[]", Some("empty")),
+            (r#"[1, "2"]"#, None),
+            ("[true, null]", None),
+            ("[[], {}]", None),
+            (r#"{"a": 1}"#, None),
+            ("1", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                Value::from_str(input).unwrap().is_homogeneous_array(),
+                expected,
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_array_map() {
+        let value = Value::from_str(r#"[1, 2.5, -3, "x"]"#).unwrap();
+        let doubled = value
+            .array_map(|element| match element {
+                Value::Number(n) => Value::Number(n * 2.0),
+                other => other.clone(),
+            })
+            .unwrap();
+        assert_eq!(doubled, Value::from_str(r#"[2, 5, -6, "x"]"#).unwrap());
+
+        assert_eq!(
+            Value::Array(vec![]).array_map(|_| Value::Null),
+            Some(Value::Array(vec![]))
+        );
+        assert_eq!(
+            Value::from_str(r#"{"a": 1}"#)
+                .unwrap()
+                .array_map(Value::clone),
+            None
+        );
+    }
+
+    #[test]
+    fn test_array_slice() {
+        let value = Value::from_str(r#"[0, 1, 2, 3, "four"]"#).unwrap();
+        assert_eq!(
+            value.array_slice(1, 3),
+            Some(vec![&Value::Number(1.0), &Value::Number(2.0)])
+        );
+        assert_eq!(
+            value.array_slice(3, 100),
+            Some(vec![
+                &Value::Number(3.0),
+                &Value::String("four".to_string())
+            ])
+        );
+        assert_eq!(value.array_slice(4, 2), Some(vec![]));
+        assert_eq!(value.array_slice(10, 20), Some(vec![]));
+        assert_eq!(
+            Value::from_str(r#"{"a": 1}"#).unwrap().array_slice(0, 1),
+            None
+        );
+    }
+
+    #[test]
+    fn test_mutable_accessors() {
+        let mut value = Value::from_str(r#"{"list": [1, 2], "n": 3}"#).unwrap();
+
+        let object = value.as_object_mut().unwrap();
+        object.remove("n");
+        object.insert("added".to_string(), Value::Null);
+        let list = object.get_mut("list").unwrap().as_array_mut().unwrap();
+        list.push(Value::Number(3.0));
+        list.swap(0, 2);
+
+        assert_eq!(
+            value,
+            Value::from_str(r#"{"list": [3, 2, 1], "added": null}"#).unwrap()
+        );
+        assert!(value.as_array_mut().is_none());
+        assert!(Value::Null.as_object_mut().is_none());
+    }
+
+    #[test]
+    fn test_ensure_containers_from_null() {
+        let mut value = Value::Null;
+        let root = value.ensure_object();
+        root.insert("name".to_string(), Value::String("x".to_string()));
+        let tags = root.entry("tags".to_string()).or_insert(Value::Null);
+        tags.ensure_array().push(Value::Number(1.0));
+        tags.ensure_array().push(Value::Number(2.0));
+        value
+            .ensure_object()
+            .entry("meta".to_string())
+            .or_insert(Value::Null)
+            .ensure_object()
+            .insert("ok".to_string(), Value::Boolean(true));
+
+        assert_eq!(
+            value,
+            Value::from_str(r#"{"name": "x", "tags": [1, 2], "meta": {"ok": true}}"#).unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "ensure_object called on array")]
+    fn test_ensure_object_panics_on_mismatch() {
+        Value::Array(vec![]).ensure_object();
+    }
+
+    #[test]
+    #[should_panic(expected = "ensure_array called on string")]
+    fn test_ensure_array_panics_on_mismatch() {
+        Value::String("x".to_string()).ensure_array();
+    }
+
+    #[test]
+    fn test_non_string_key_errors() {
+        let err = Value::from_str(r#"{"a": 1, 2: 3}"#).unwrap_err();
+        assert_eq!(err.message, "Expected string key in object, found '2'");
+        assert_eq!(err.offset, 9);
+
+        let err = Value::from_str(r#"{"a": 1,}"#).unwrap_err();
+        assert_eq!(err.message, "Expected string key in object, found '}'");
+        assert_eq!(err.offset, 8);
+
+        let err = Value::from_str(r#"{"a": 1, "#).unwrap_err();
+        assert_eq!(
+            err.message,
+            "Expected string key in object, found end of input"
+        );
+    }
+
+    #[test]
+    fn test_unquoted_keys() {
+        let input = r#"{name: "x", _id$2: 1, "quoted": true}"#;
+
+        let err = Value::from_str(input).unwrap_err();
+        assert_eq!(
+            err.message,
+            "Expected string key in object, found unquoted key 'name'"
+        );
+        assert_eq!(err.offset, 1);
+
+        let options = ParseOptions {
+            allow_unquoted_keys: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            Value::from_str_with_options(input, &options).unwrap(),
+            Value::from_str(r#"{"name": "x", "_id$2": 1, "quoted": true}"#).unwrap()
+        );
+
+        let err = Value::from_str_with_options("{1a: 2}", &options).unwrap_err();
+        assert_eq!(err.message, "Expected string key in object, found '1'");
+    }
+
+    #[test]
+    fn test_single_quotes() {
+        let options = ParseOptions {
+            allow_single_quotes: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            Value::from_str_with_options("'hi'", &options).unwrap(),
+            Value::String("hi".to_string())
+        );
+        assert_eq!(
+            Value::from_str_with_options(r#"{'a': 'it\'s "quoted"\n', "b": 'x'}"#, &options)
+                .unwrap(),
+            Value::from_str(r#"{"a": "it's \"quoted\"\n", "b": "x"}"#).unwrap()
+        );
+
+        let err = Value::from_str("'hi'").unwrap_err();
+        assert_eq!(err.message, "Unexpected character: '''");
+        let err = Value::from_str("{'a': 1}").unwrap_err();
+        assert_eq!(err.message, "Expected string key in object, found '''");
+        assert!(Value::from_str(r#""it\'s""#).is_err());
+    }
+
+    #[test]
+    fn test_comments() {
+        let options = ParseOptions {
+            allow_comments: true,
+            ..Default::default()
+        };
+        let input = "// leading\n[1, /* inline */ 2 // trailing\n, /**/3]/* end */";
+        assert_eq!(
+            Value::from_str_with_options(input, &options).unwrap(),
+            Value::from_str("[1, 2, 3]").unwrap()
+        );
+
+        let err = Value::from_str_with_options("[1 /* open", &options).unwrap_err();
+        assert_eq!(err.message, "Expected ',' or ']' in array");
+        assert_eq!(err.offset, 3);
+        assert!(Value::from_str(input).is_err());
+    }
+
+    #[test]
+    fn test_trailing_commas() {
+        let options = ParseOptions {
+            allow_trailing_commas: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            Value::from_str_with_options(r#"{"a": [1, 2, ], "b": {"c": 3,},}"#, &options).unwrap(),
+            Value::from_str(r#"{"a": [1, 2], "b": {"c": 3}}"#).unwrap()
+        );
+        assert!(Value::from_str_with_options("[,]", &options).is_err());
+        assert!(Value::from_str_with_options("[1,,]", &options).is_err());
+
+        let err = Value::from_str("[1,]").unwrap_err();
+        assert_eq!(err.message, "Unexpected character: ']'");
+        let err = Value::from_str(r#"{"a": 1,}"#).unwrap_err();
+        assert_eq!(err.message, "Expected string key in object, found '}'");
+    }
+
+    #[test]
+    fn test_nan_infinity() {
+        let options = ParseOptions {
+            allow_nan_infinity: true,
+            ..Default::default()
+        };
+        let value =
+            Value::from_str_with_options("[NaN, Infinity, -Infinity, -1]", &options).unwrap();
+        let numbers = value.as_f64_array().unwrap();
+        assert!(numbers[0].is_nan());
+        assert_eq!(&numbers[1..], &[f64::INFINITY, f64::NEG_INFINITY, -1.0]);
+
+        assert_eq!(
+            Value::from_str("NaN").unwrap_err().message,
+            "Unexpected character: 'N'"
+        );
+        assert!(Value::from_str("-Infinity").is_err());
+        assert!(Value::from_str_with_options("nan", &options).is_err());
+    }
+
+    #[test]
+    fn test_unicode_whitespace() {
+        let input = "[1,\u{a0}2]";
+        let err = Value::from_str(input).unwrap_err();
+        assert_eq!(err.message, "Unexpected character: '\u{a0}'");
+        assert_eq!(err.column, 4);
+        assert!(Value::from_str("[1,\u{c}2]").is_err());
+
+        let options = ParseOptions {
+            allow_unicode_whitespace: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            Value::from_str_with_options(input, &options).unwrap(),
+            Value::from_str("[1, 2]").unwrap()
+        );
+        assert!(Value::from_str(" \t\r\n[1,\t2]\n").is_ok());
+    }
+
+    #[test]
+    fn test_parse_relaxed() {
+        let input = r#"
+            // Service config
+            {
+                name: 'web',
+                ports: [80, 443,],   /* both protocols */
+                "ratio": Infinity,
+                nested: {'quote': 'it\'s', limit: -Infinity,},
+            }
+        "#;
+        let value = Value::parse_relaxed(input).unwrap();
+        assert_eq!(
+            value,
+            Value::Object(
+                [
+                    ("name", Value::String("web".to_string())),
+                    ("ports", Value::from_str("[80, 443]").unwrap()),
+                    ("ratio", Value::Number(f64::INFINITY)),
+                    (
+                        "nested",
+                        Value::Object(
+                            [
+                                ("quote", Value::String("it's".to_string())),
+                                ("limit", Value::Number(f64::NEG_INFINITY)),
+                            ]
+                            .into_iter()
+                            .map(|(k, v)| (k.to_string(), v))
+                            .collect()
+                        )
+                    ),
+                ]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect()
+            )
+        );
+        assert!(Value::from_str(input).is_err());
+    }
+
+    fn parse_with_duplicates(input: &str, policy: DuplicateKeys) -> Result<Value, ParseError> {
+        let options = ParseOptions {
+            duplicate_keys: policy,
+            ..Default::default()
+        };
+        Value::from_str_with_options(input, &options)
+    }
+
+    #[test]
+    fn test_duplicate_keys_last_wins() {
+        let result = parse_with_duplicates(r#"{"a":1,"a":2}"#, DuplicateKeys::LastWins).unwrap();
+        assert_eq!(result, Value::from_str(r#"{"a":2}"#).unwrap());
+        assert_eq!(result, Value::from_str(r#"{"a":1,"a":2}"#).unwrap());
+    }
+
+    #[test]
+    fn test_duplicate_keys_error() {
+        let err = parse_with_duplicates(r#"{"a":1, "a":2}"#, DuplicateKeys::Error).unwrap_err();
+        assert_eq!(err.message, "Duplicate key 'a' in object");
+        assert_eq!(err.offset, 8);
+    }
+
+    #[test]
+    fn test_duplicate_keys_collect() {
+        let result = parse_with_duplicates(r#"{"a":1,"a":2}"#, DuplicateKeys::Collect).unwrap();
+        assert_eq!(result, Value::from_str(r#"{"a":[1,2]}"#).unwrap());
+
+        let result = parse_with_duplicates(
+            r#"{"a":[1],"b":true,"a":2,"a":{"c":3}}"#,
+            DuplicateKeys::Collect,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            Value::from_str(r#"{"a":[[1],2,{"c":3}],"b":true}"#).unwrap()
+        );
+    }
 }
@@ -0,0 +1,384 @@
+use crate::pointer::push_token;
+use crate::Value;
+
+/// A place where `deep_merge_checked` replaced a value with one of a
+/// different JSON type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeConflict {
+    /// JSON Pointer to the replaced value
+    pub path: String,
+    /// Type name of the value that was there before, e.g. `"number"`
+    pub old_type: &'static str,
+    /// Type name of the value that replaced it
+    pub new_type: &'static str,
+}
+
+impl Value {
+    /// Merge `other` into `self`, recursing into objects
+    ///
+    /// When both sides are objects, keys from `other` are merged into `self`
+    /// one by one: new keys are inserted and keys present on both sides are
+    /// merged recursively. In every other case `other` replaces `self`
+    /// outright, so arrays are replaced rather than concatenated and a
+    /// `null` in `other` overwrites.
+    pub fn deep_merge(&mut self, mut other: Value) {
+        match (self, &mut other) {
+            (Value::Object(target), Value::Object(source)) => {
+                for (key, value) in source.drain() {
+                    match target.get_mut(&key) {
+                        Some(existing) => existing.deep_merge(value),
+                        None => {
+                            target.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (target, _) => *target = other,
+        }
+    }
+
+    /// Same as `deep_merge`, but report every path where the merge replaced
+    /// a value with one of a different type
+    ///
+    /// Such replacements usually point at a mistake in layered config, like
+    /// a string overriding a number. Replacing with or by `null` counts as a
+    /// type change too. The merge itself is carried out exactly as with
+    /// `deep_merge`. Conflicts are listed in the order they were found.
+    pub fn deep_merge_checked(&mut self, other: Value) -> Vec<TypeConflict> {
+        let mut conflicts = Vec::new();
+        self.deep_merge_checked_at(other, &mut String::new(), &mut conflicts);
+        conflicts
+    }
+
+    fn deep_merge_checked_at(
+        &mut self,
+        mut other: Value,
+        path: &mut String,
+        conflicts: &mut Vec<TypeConflict>,
+    ) {
+        match (self, &mut other) {
+            (Value::Object(target), Value::Object(source)) => {
+                for (key, value) in source.drain() {
+                    match target.get_mut(&key) {
+                        Some(existing) => {
+                            let len = path.len();
+                            push_token(path, &key);
+                            existing.deep_merge_checked_at(value, path, conflicts);
+                            path.truncate(len);
+                        }
+                        None => {
+                            target.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (target, _) => {
+                if target.type_name() != other.type_name() {
+                    conflicts.push(TypeConflict {
+                        path: path.clone(),
+                        old_type: target.type_name(),
+                        new_type: other.type_name(),
+                    });
+                }
+                *target = other;
+            }
+        }
+    }
+
+    /// Same as `deep_merge`, but let `resolver` decide every conflict
+    ///
+    /// A conflict is a path present on both sides where the two values are
+    /// not both objects. `resolver` is called with the JSON Pointer to it,
+    /// the current value and the incoming one, and its result is stored
+    /// there. Objects on both sides are still merged key by key, and keys
+    /// only in `other` are inserted as they are. `deep_merge` is this with a
+    /// resolver that returns the incoming value.
+    pub fn merge_with(&mut self, other: Value, resolver: impl Fn(&str, &Value, &Value) -> Value) {
+        self.merge_with_at(other, &mut String::new(), &resolver);
+    }
+
+    fn merge_with_at(
+        &mut self,
+        mut other: Value,
+        path: &mut String,
+        resolver: &impl Fn(&str, &Value, &Value) -> Value,
+    ) {
+        match (self, &mut other) {
+            (Value::Object(target), Value::Object(source)) => {
+                for (key, value) in source.drain() {
+                    match target.get_mut(&key) {
+                        Some(existing) => {
+                            let len = path.len();
+                            push_token(path, &key);
+                            existing.merge_with_at(value, path, resolver);
+                            path.truncate(len);
+                        }
+                        None => {
+                            target.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (target, _) => *target = resolver(path, target, &other),
+        }
+    }
+
+    /// Apply a JSON merge patch (RFC 7386)
+    ///
+    /// An object patch is applied member by member: `null` removes the
+    /// member, and anything else is merged into it recursively, turning a
+    /// non-object target into an empty object first. Any other patch
+    /// replaces `self` outright.
+    pub fn apply_merge_patch(&mut self, mut patch: Value) {
+        let Value::Object(members) = &mut patch else {
+            *self = patch;
+            return;
+        };
+        let members = std::mem::take(members);
+
+        if !matches!(self, Value::Object(_)) {
+            *self = Value::Null;
+        }
+        let target = self.ensure_object();
+        for (key, value) in members {
+            if value.is_null() {
+                target.remove(&key);
+            } else {
+                target
+                    .entry(key)
+                    .or_insert(Value::Null)
+                    .apply_merge_patch(value);
+            }
+        }
+    }
+
+    /// Append the elements of the array `other` to the array `self`
+    ///
+    /// If either side is not an array, nothing changes and `other` is handed
+    /// back as the error.
+    pub fn array_concat(&mut self, mut other: Value) -> Result<(), Value> {
+        match (self, &mut other) {
+            (Value::Array(target), Value::Array(source)) => {
+                target.append(source);
+                Ok(())
+            }
+            _ => Err(other),
+        }
+    }
+
+    /// Insert the members of the object `other` into the object `self`,
+    /// replacing existing members with the same key
+    ///
+    /// Unlike `deep_merge` this is shallow: a nested object in `other`
+    /// replaces the one in `self` rather than being merged into it. If either
+    /// side is not an object, nothing changes and `other` is handed back as
+    /// the error.
+    pub fn object_extend(&mut self, mut other: Value) -> Result<(), Value> {
+        match (self, &mut other) {
+            (Value::Object(target), Value::Object(source)) => {
+                target.extend(source.drain());
+                Ok(())
+            }
+            _ => Err(other),
+        }
+    }
+
+    /// Deep-merge a list of documents left to right, so later documents
+    /// override earlier ones
+    ///
+    /// This is the "defaults, then environment, then overrides" layering in
+    /// one call. An empty slice gives `Null`.
+    pub fn merge_all(docs: &[Value]) -> Value {
+        docs.iter().fold(Value::Null, |mut merged, doc| {
+            merged.deep_merge(doc.clone());
+            merged
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Value {
+        Value::from_str(input).unwrap()
+    }
+
+    #[test]
+    fn test_deep_merge() {
+        let mut value = parse(r#"{"a": 1, "b": {"c": 2, "d": [1, 2]}, "e": "x"}"#);
+        value.deep_merge(parse(r#"{"b": {"d": [3], "f": null}, "e": {"g": true}}"#));
+        assert_eq!(
+            value,
+            parse(r#"{"a": 1, "b": {"c": 2, "d": [3], "f": null}, "e": {"g": true}}"#)
+        );
+
+        let mut scalar = parse("[1]");
+        scalar.deep_merge(parse(r#"{"a": 1}"#));
+        assert_eq!(scalar, parse(r#"{"a": 1}"#));
+    }
+
+    #[test]
+    fn test_deep_merge_checked_reports_type_changes() {
+        let mut value = parse(r#"{"port": 8080, "db": {"hosts": ["a"], "a/b": 1}, "name": "x"}"#);
+        let conflicts = value.deep_merge_checked(parse(
+            r#"{"port": "8080", "db": {"hosts": ["b"], "a/b": {"c": 1}}, "name": "y", "new": 1}"#,
+        ));
+
+        let mut paths: Vec<(&str, &str, &str)> = conflicts
+            .iter()
+            .map(|c| (c.path.as_str(), c.old_type, c.new_type))
+            .collect();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                ("/db/a~1b", "number", "object"),
+                ("/port", "number", "string")
+            ]
+        );
+
+        // The merge itself matches deep_merge
+        let mut expected =
+            parse(r#"{"port": 8080, "db": {"hosts": ["a"], "a/b": 1}, "name": "x"}"#);
+        expected.deep_merge(parse(
+            r#"{"port": "8080", "db": {"hosts": ["b"], "a/b": {"c": 1}}, "name": "y", "new": 1}"#,
+        ));
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn test_deep_merge_checked_root_and_null() {
+        let mut value = parse("[1]");
+        assert_eq!(
+            value.deep_merge_checked(parse("null")),
+            vec![TypeConflict {
+                path: String::new(),
+                old_type: "array",
+                new_type: "null",
+            }]
+        );
+        assert!(parse(r#"{"a": 1}"#)
+            .deep_merge_checked(parse(r#"{"a": 2}"#))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_merge_with_sums_numbers() {
+        let mut value = parse(r#"{"hits": 3, "stats": {"ms": 10, "errors": 1}, "name": "a"}"#);
+        let paths = std::cell::RefCell::new(Vec::new());
+        value.merge_with(
+            parse(r#"{"hits": 4, "stats": {"ms": 5, "p99": 7}, "name": "b"}"#),
+            |path, old, new| {
+                paths.borrow_mut().push(path.to_string());
+                match (old, new) {
+                    (Value::Number(a), Value::Number(b)) => Value::Number(a + b),
+                    _ => new.clone(),
+                }
+            },
+        );
+
+        assert_eq!(
+            value,
+            parse(r#"{"hits": 7, "stats": {"ms": 15, "errors": 1, "p99": 7}, "name": "b"}"#)
+        );
+        let mut paths = paths.into_inner();
+        paths.sort();
+        assert_eq!(paths, vec!["/hits", "/name", "/stats/ms"]);
+    }
+
+    #[test]
+    fn test_merge_with_concatenates_arrays() {
+        let mut value = parse(r#"{"tags": ["a"], "n": 1}"#);
+        value.merge_with(parse(r#"{"tags": ["b"], "n": {"x": 1}}"#), |_, old, new| {
+            let mut merged = old.clone();
+            match merged.array_concat(new.clone()) {
+                Ok(()) => merged,
+                Err(new) => new,
+            }
+        });
+        assert_eq!(value, parse(r#"{"tags": ["a", "b"], "n": {"x": 1}}"#));
+
+        let mut root = parse("1");
+        root.merge_with(parse("2"), |path, _, _| Value::String(path.to_string()));
+        assert_eq!(root, Value::String(String::new()));
+    }
+
+    #[test]
+    fn test_apply_merge_patch() {
+        // The example from RFC 7386
+        let mut value = parse(
+            r#"{"title": "Goodbye!", "author": {"givenName": "John", "familyName": "Doe"}, "tags": ["example", "sample"], "content": "This will be unchanged"}"#,
+        );
+        value.apply_merge_patch(parse(
+            r#"{"title": "Hello!", "phoneNumber": "+01-123-456-7890", "author": {"familyName": null}, "tags": ["example"]}"#,
+        ));
+        assert_eq!(
+            value,
+            parse(
+                r#"{"title": "Hello!", "author": {"givenName": "John"}, "tags": ["example"], "content": "This will be unchanged", "phoneNumber": "+01-123-456-7890"}"#
+            )
+        );
+
+        let mut scalar = parse("[1]");
+        scalar.apply_merge_patch(parse(r#"{"a": {"b": null, "c": 1}}"#));
+        assert_eq!(scalar, parse(r#"{"a": {"c": 1}}"#));
+        scalar.apply_merge_patch(parse("null"));
+        assert_eq!(scalar, Value::Null);
+    }
+
+    #[test]
+    fn test_array_concat() {
+        let mut value = parse("[1, [2]]");
+        value.array_concat(parse(r#"[3, {"a": 4}]"#)).unwrap();
+        assert_eq!(value, parse(r#"[1, [2], 3, {"a": 4}]"#));
+
+        value.array_concat(parse("[]")).unwrap();
+        assert_eq!(value, parse(r#"[1, [2], 3, {"a": 4}]"#));
+    }
+
+    #[test]
+    fn test_object_extend_is_shallow() {
+        let mut value = parse(r#"{"a": 1, "b": {"c": 2, "d": 3}}"#);
+        value
+            .object_extend(parse(r#"{"b": {"c": 5}, "e": null}"#))
+            .unwrap();
+        assert_eq!(value, parse(r#"{"a": 1, "b": {"c": 5}, "e": null}"#));
+    }
+
+    #[test]
+    fn test_concat_and_extend_type_mismatch() {
+        let mut array = parse("[1]");
+        assert_eq!(
+            array.array_concat(parse(r#"{"a": 1}"#)),
+            Err(parse(r#"{"a": 1}"#))
+        );
+        assert_eq!(array, parse("[1]"));
+
+        let mut object = parse(r#"{"a": 1}"#);
+        assert_eq!(object.object_extend(parse("[2]")), Err(parse("[2]")));
+        assert_eq!(object, parse(r#"{"a": 1}"#));
+
+        let mut scalar = Value::Null;
+        assert_eq!(scalar.array_concat(parse("[1]")), Err(parse("[1]")));
+        assert_eq!(scalar.object_extend(parse("{}")), Err(parse("{}")));
+        assert_eq!(scalar, Value::Null);
+    }
+
+    #[test]
+    fn test_merge_all_layers_config() {
+        let defaults = parse(
+            r#"{"server": {"host": "localhost", "port": 80}, "debug": false, "tags": ["base"]}"#,
+        );
+        let environment = parse(r#"{"server": {"host": "prod.example.com"}, "tags": ["prod"]}"#);
+        let overrides = parse(r#"{"server": {"port": 8080}, "debug": true}"#);
+
+        assert_eq!(
+            Value::merge_all(&[defaults, environment, overrides]),
+            parse(
+                r#"{"server": {"host": "prod.example.com", "port": 8080}, "debug": true, "tags": ["prod"]}"#
+            )
+        );
+        assert_eq!(Value::merge_all(&[]), Value::Null);
+    }
+}
@@ -0,0 +1,1004 @@
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use crate::arena::{Document, DocumentBuilder};
+use crate::borrowed::{BorrowedBuilder, BorrowedValue};
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Value;
+
+/// What to do when an object contains the same key more than once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKeys {
+    /// Keep the last value seen for the key
+    #[default]
+    LastWins,
+    /// Reject the document with a "Duplicate key" error
+    Error,
+    /// Keep every value: a repeated key maps to an array of all its values
+    /// in document order, so `{"a":1,"a":2}` parses as `{"a":[1,2]}`.
+    ///
+    /// Keys that appear only once keep their plain value, which means
+    /// `{"a":[1,2]}` and `{"a":1,"a":2}` produce the same result.
+    Collect,
+}
+
+/// Options controlling how input is parsed
+///
+/// The default is strict JSON with last-wins duplicate keys, exactly what
+/// `Value::from_str` does. The `allow_*` flags each turn on one JSON5-style
+/// extension.
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Policy for repeated keys within one object
+    pub duplicate_keys: DuplicateKeys,
+    /// Accept bare identifier keys such as `{name: "x"}`. An identifier is
+    /// an ASCII letter, `_` or `$` followed by letters, digits, `_` or `$`.
+    pub allow_unquoted_keys: bool,
+    /// Accept single-quoted strings and keys such as `'hello'`, with the
+    /// same escapes as double-quoted ones plus `\'`
+    pub allow_single_quotes: bool,
+    /// Reject numbers longer than this many characters with
+    /// `ParseErrorKind::NumberTooLong`, so hostile input cannot make number
+    /// conversion arbitrarily slow. `None` (the default) means no limit.
+    pub max_number_length: Option<usize>,
+    /// Reject object keys longer than this many bytes (after unescaping)
+    /// with `ParseErrorKind::KeyTooLong`. `None` (the default) means no
+    /// limit.
+    pub max_key_length: Option<usize>,
+    /// When building a `Document`, store each distinct object key once and
+    /// let every member with that key share it. Arrays of records repeat the
+    /// same few keys thousands of times, so this shrinks the document's
+    /// text buffer considerably. `Value` trees are unaffected, since each
+    /// `HashMap` owns its keys.
+    pub intern_keys: bool,
+    /// When building a `Document`, keep the source text of every number, so
+    /// `NodeRef::number_source` returns it and the document is written back
+    /// with exactly the digits it was read with, `1.10` staying `1.10`.
+    /// `Value` trees are unaffected: `Value::Number` holds only the `f64`.
+    pub keep_number_source: bool,
+    /// Accept `// line` and `/* block */` comments wherever whitespace is
+    /// allowed
+    pub allow_comments: bool,
+    /// Accept a comma after the last element of an array or member of an
+    /// object, as in `[1, 2,]`
+    pub allow_trailing_commas: bool,
+    /// Accept `NaN`, `Infinity` and `-Infinity` as numbers
+    pub allow_nan_infinity: bool,
+    /// Skip any Unicode whitespace between tokens, such as a no-break
+    /// space. Strict JSON only allows space, tab, `\n` and `\r`.
+    pub allow_unicode_whitespace: bool,
+}
+
+impl ParseOptions {
+    /// Options that turn on every lenient extension at once: comments,
+    /// trailing commas, single quotes, unquoted keys, `NaN`/`Infinity`, and
+    /// Unicode whitespace.
+    /// Everything else keeps its default.
+    pub fn relaxed() -> Self {
+        Self {
+            allow_unquoted_keys: true,
+            allow_single_quotes: true,
+            allow_comments: true,
+            allow_trailing_commas: true,
+            allow_nan_infinity: true,
+            allow_unicode_whitespace: true,
+            ..Default::default()
+        }
+    }
+}
+
+/// Simple JSON parser
+///
+/// Works directly on the UTF-8 bytes of the input. Every structural character
+/// in JSON is ASCII, so positions always land on char boundaries and string
+/// contents can be sliced out of the input without copying.
+///
+/// Nesting is tracked on an explicit stack of open containers rather than by
+/// recursion, so deeply nested input is bounded by the heap and can never
+/// overflow the call stack. The same walk builds every kind of output; a
+/// `Builder` decides how the values it reports are stored.
+pub(crate) struct Parser<'a> {
+    input: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+    options: ParseOptions,
+}
+
+/// A container the parser has opened but not yet closed
+enum Frame<K> {
+    /// An array, with the mark returned by `Builder::start_array`
+    Array(usize),
+    /// An object, with the mark returned by `Builder::start_object` and the
+    /// key of the member whose value is being parsed
+    Object(usize, K),
+}
+
+/// Receives the values of a document as the parser reads them
+///
+/// Scalars are reported as they are parsed. A container is opened with
+/// `start_*`, which returns a mark for the matching `finish_*`; its children
+/// are pushed in between, each one complete before it is pushed.
+pub(crate) trait Builder<'a> {
+    /// A finished value
+    type Node;
+    /// An object key waiting for its value
+    type Key;
+
+    fn null(&mut self) -> Self::Node;
+    fn boolean(&mut self, b: bool) -> Self::Node;
+    /// A number, with the text it was parsed from
+    fn number(&mut self, n: f64, source: &'a str) -> Self::Node;
+    fn string(&mut self, s: Cow<'a, str>) -> Self::Node;
+
+    fn start_array(&mut self) -> usize;
+    fn push_element(&mut self, element: Self::Node);
+    fn finish_array(&mut self, mark: usize) -> Self::Node;
+
+    fn start_object(&mut self) -> usize;
+    /// Take the key of the next member; `offset` is where the key starts in
+    /// the input
+    fn key(&mut self, key: Cow<'a, str>, offset: usize) -> Self::Key;
+    fn push_member(&mut self, key: Self::Key, value: Self::Node);
+    fn finish_object(&mut self, mark: usize) -> Result<Self::Node, ParseError>;
+}
+
+impl<'a> Parser<'a> {
+    pub(crate) fn new(input: &'a str) -> Self {
+        Self::with_options(input, ParseOptions::default())
+    }
+
+    pub(crate) fn with_options(input: &'a str, options: ParseOptions) -> Self {
+        Self {
+            input,
+            bytes: input.as_bytes(),
+            pos: 0,
+            options,
+        }
+    }
+
+    pub(crate) fn parse(&mut self) -> Result<Value, ParseError> {
+        let mut builder = ValueBuilder::new(self.input, self.options.duplicate_keys);
+        let value = self.parse_with(&mut builder)?;
+        self.finish()?;
+        Ok(value)
+    }
+
+    pub(crate) fn parse_borrowed(&mut self) -> Result<BorrowedValue<'a>, ParseError> {
+        let value = self.parse_with(&mut BorrowedBuilder::default())?;
+        self.finish()?;
+        Ok(value)
+    }
+
+    /// Walk down to the value at a JSON Pointer and parse only that value
+    ///
+    /// Containers along the path are scanned member by member and everything
+    /// off the path is skipped without being built. Returns `None` when the
+    /// path does not exist. Input after the target is not examined.
+    pub(crate) fn extract(&mut self, tokens: &[String]) -> Result<Option<Value>, ParseError> {
+        self.skip_whitespace();
+
+        for token in tokens {
+            let found = match self.peek() {
+                Some(b'{') => self.seek_member(token)?,
+                Some(b'[') => match array_index(token) {
+                    Some(index) => self.seek_element(index)?,
+                    None => false,
+                },
+                _ => {
+                    // A scalar has no children to descend into
+                    self.skip_value()?;
+                    false
+                }
+            };
+
+            if !found {
+                return Ok(None);
+            }
+            self.skip_whitespace();
+        }
+
+        let mut builder = ValueBuilder::new(self.input, self.options.duplicate_keys);
+        self.parse_with(&mut builder).map(Some)
+    }
+
+    /// Advance to the value of the member named `key` in the object at the
+    /// current position. Returns `false` if the object has no such key.
+    ///
+    /// If the key repeats, the last occurrence is used, as in a full parse,
+    /// so the rest of the object is scanned before coming back to it.
+    fn seek_member(&mut self, key: &str) -> Result<bool, ParseError> {
+        self.pos += 1; // consume '{'
+        self.skip_whitespace();
+
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(false);
+        }
+
+        let mut found = None;
+        loop {
+            if self.parse_object_key()? == key {
+                found = Some(self.pos);
+            }
+            self.skip_value()?;
+
+            if self.parse_object_separator()? {
+                break;
+            }
+        }
+
+        match found {
+            Some(pos) => {
+                self.pos = pos;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Advance to element `index` of the array at the current position.
+    /// Returns `false` if the array is too short.
+    fn seek_element(&mut self, index: usize) -> Result<bool, ParseError> {
+        self.pos += 1; // consume '['
+        self.skip_whitespace();
+
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(false);
+        }
+
+        let mut current = 0;
+        loop {
+            if current == index {
+                return Ok(true);
+            }
+            self.skip_value()?;
+
+            if self.parse_array_separator()? {
+                return Ok(false);
+            }
+            current += 1;
+        }
+    }
+
+    /// Check the syntax of the value at the current position and move past
+    /// it without building anything. Strings are only copied when they
+    /// contain escapes.
+    fn skip_value(&mut self) -> Result<(), ParseError> {
+        self.parse_with(&mut SkipBuilder)
+    }
+
+    /// Parse the whole input as a single JSON number, with no surrounding
+    /// whitespace. Returns `None` if the input is anything else.
+    pub(crate) fn parse_number_exact(&mut self) -> Option<f64> {
+        let n = self.parse_number().ok()?;
+        (self.pos == self.bytes.len()).then_some(n)
+    }
+
+    pub(crate) fn parse_document(&mut self) -> Result<Document, ParseError> {
+        let mut builder = DocumentBuilder::new(self.input, &self.options);
+        self.parse_with(&mut builder)?;
+        self.finish()?;
+        Ok(builder.finish())
+    }
+
+    fn finish(&mut self) -> Result<(), ParseError> {
+        self.skip_whitespace();
+
+        if self.pos < self.bytes.len() {
+            return Err(self.error("Unexpected characters after JSON value"));
+        }
+
+        Ok(())
+    }
+
+    /// Parse the value at the current position into `builder`
+    ///
+    /// Containers that are still open live on `stack`, innermost last. Each
+    /// turn of the outer loop reads one value; a container only gets as far
+    /// as its first child before the loop moves on to read that child. A
+    /// finished value is then handed to the container it belongs to, which
+    /// may close in turn and be handed to its own parent.
+    fn parse_with<B: Builder<'a>>(&mut self, builder: &mut B) -> Result<B::Node, ParseError> {
+        let mut stack: Vec<Frame<B::Key>> = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+
+            let mut value = match self.peek() {
+                None => return Err(self.error("Unexpected end of input")),
+                Some(b'n') => {
+                    self.parse_null()?;
+                    builder.null()
+                }
+                Some(b't' | b'f') => {
+                    let b = self.parse_boolean()?;
+                    builder.boolean(b)
+                }
+                _ if self.at_string() => {
+                    let s = self.parse_string()?;
+                    builder.string(s)
+                }
+                Some(b'-' | b'0'..=b'9') => {
+                    let start = self.pos;
+                    let n = self.parse_number()?;
+                    builder.number(n, &self.input[start..self.pos])
+                }
+                Some(b'N' | b'I') if self.options.allow_nan_infinity => {
+                    let start = self.pos;
+                    let n = self.parse_number()?;
+                    builder.number(n, &self.input[start..self.pos])
+                }
+                Some(b'[') => {
+                    self.pos += 1; // consume '['
+                    let mark = builder.start_array();
+                    self.skip_whitespace();
+
+                    // Empty array
+                    if self.peek() != Some(b']') {
+                        stack.push(Frame::Array(mark));
+                        continue;
+                    }
+                    self.pos += 1;
+                    builder.finish_array(mark)
+                }
+                Some(b'{') => {
+                    self.pos += 1; // consume '{'
+                    let mark = builder.start_object();
+                    self.skip_whitespace();
+
+                    // Empty object
+                    if self.peek() != Some(b'}') {
+                        let key = self.parse_member_key(builder)?;
+                        stack.push(Frame::Object(mark, key));
+                        continue;
+                    }
+                    self.pos += 1;
+                    builder.finish_object(mark)?
+                }
+                Some(_) => return Err(self.unexpected_character()),
+            };
+
+            // Hand the value up, closing every container that ends with it
+            loop {
+                let Some(frame) = stack.pop() else {
+                    return Ok(value);
+                };
+
+                match frame {
+                    Frame::Array(mark) => {
+                        builder.push_element(value);
+
+                        if self.parse_array_separator()? {
+                            value = builder.finish_array(mark);
+                        } else {
+                            stack.push(Frame::Array(mark));
+                            break;
+                        }
+                    }
+                    Frame::Object(mark, key) => {
+                        builder.push_member(key, value);
+
+                        if self.parse_object_separator()? {
+                            value = builder.finish_object(mark)?;
+                        } else {
+                            let key = self.parse_member_key(builder)?;
+                            stack.push(Frame::Object(mark, key));
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Parse an object key and its ':' and hand the key to `builder`
+    fn parse_member_key<B: Builder<'a>>(&mut self, builder: &mut B) -> Result<B::Key, ParseError> {
+        self.skip_whitespace();
+        let key_start = self.pos;
+        let key = self.parse_object_key()?;
+        Ok(builder.key(key, key_start))
+    }
+
+    fn unexpected_character(&self) -> ParseError {
+        let c = self.input[self.pos..].chars().next().unwrap_or_default();
+        self.error(format!("Unexpected character: '{}'", c))
+    }
+
+    /// Build an error pointing at the current position
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError::new(message, self.input, self.pos)
+    }
+
+    fn error_of_kind(&self, kind: ParseErrorKind, message: impl Into<String>) -> ParseError {
+        ParseError::with_kind(kind, message, self.input, self.pos)
+    }
+
+    fn parse_null(&mut self) -> Result<(), ParseError> {
+        self.expect_literal("null", "Invalid null literal")
+    }
+
+    fn parse_boolean(&mut self) -> Result<bool, ParseError> {
+        let (literal, value) = if self.peek() == Some(b't') {
+            ("true", true)
+        } else {
+            ("false", false)
+        };
+        self.expect_literal(literal, "Invalid boolean literal")
+            .map(|_| value)
+    }
+
+    /// Consume `literal`, failing with `message` if something else is there.
+    /// Input that ends partway through the literal is reported as
+    /// `ParseErrorKind::UnfinishedLiteral` instead, at the literal's start.
+    fn expect_literal(&mut self, literal: &str, message: &str) -> Result<(), ParseError> {
+        if self.consume_literal(literal) {
+            return Ok(());
+        }
+
+        let rest = &self.input[self.pos..];
+        if literal.starts_with(rest) {
+            let message = format!(
+                "Unfinished literal '{}': input ends after '{}'",
+                literal, rest
+            );
+            return Err(self.error_of_kind(ParseErrorKind::UnfinishedLiteral, message));
+        }
+        Err(self.error(message))
+    }
+
+    fn parse_number(&mut self) -> Result<f64, ParseError> {
+        if self.options.allow_nan_infinity {
+            for (literal, n) in [
+                ("NaN", f64::NAN),
+                ("Infinity", f64::INFINITY),
+                ("-Infinity", f64::NEG_INFINITY),
+            ] {
+                if self.consume_literal(literal) {
+                    return Ok(n);
+                }
+            }
+        }
+
+        let start = self.pos;
+
+        // Optional minus
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+
+        // Integer part
+        if self.peek() == Some(b'0') {
+            self.pos += 1;
+            if self.peek_digit() {
+                return Err(self.error("Invalid number: leading zero"));
+            }
+        } else if self.peek_digit() {
+            self.skip_digits();
+        } else {
+            return Err(self.error("Invalid number"));
+        }
+
+        // Optional fractional part
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            if !self.peek_digit() {
+                return Err(self.error("Invalid number: decimal point must be followed by digit"));
+            }
+            self.skip_digits();
+        }
+
+        // Optional exponent
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            if !self.peek_digit() {
+                return Err(self.error("Invalid number: exponent must have digits"));
+            }
+            self.skip_digits();
+        }
+
+        let text = &self.input[start..self.pos];
+        if let Some(limit) = self.options.max_number_length {
+            if text.len() > limit {
+                let message = format!(
+                    "Number too long: {} characters, limit is {}",
+                    text.len(),
+                    limit
+                );
+                self.pos = start;
+                return Err(self.error_of_kind(ParseErrorKind::NumberTooLong, message));
+            }
+        }
+
+        let n = text
+            .parse::<f64>()
+            .map_err(|_| self.error("Failed to parse number"))?;
+        if n.is_infinite() {
+            self.pos = start;
+            return Err(self.error_of_kind(ParseErrorKind::NumberOutOfRange, "Number out of range"));
+        }
+        Ok(n)
+    }
+
+    /// Is the current position the start of a string literal? Single quotes
+    /// count only when `allow_single_quotes` is set.
+    fn at_string(&self) -> bool {
+        match self.peek() {
+            Some(b'"') => true,
+            Some(b'\'') => self.options.allow_single_quotes,
+            _ => false,
+        }
+    }
+
+    /// Parse a string literal, borrowing it from the input when it contains
+    /// no escape sequences. The opening quote character decides which quote
+    /// ends the string.
+    fn parse_string(&mut self) -> Result<Cow<'a, str>, ParseError> {
+        let quote = self.bytes[self.pos];
+        self.pos += 1; // consume opening quote
+        self.parse_string_contents(Some(quote))
+    }
+
+    /// Parse string contents up to and including the closing `quote`, or up
+    /// to the end of the input when there is no quote
+    fn parse_string_contents(&mut self, quote: Option<u8>) -> Result<Cow<'a, str>, ParseError> {
+        let start = self.pos;
+
+        // Fast path: no escapes, so the contents are a slice of the input
+        loop {
+            let b = self.peek();
+            if b == quote {
+                let s = &self.input[start..self.pos];
+                self.pos += usize::from(b.is_some());
+                return Ok(Cow::Borrowed(s));
+            }
+            match b {
+                Some(b'\\') => break,
+                Some(_) => self.pos += 1,
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+
+        let mut result = String::from(&self.input[start..self.pos]);
+
+        loop {
+            let b = self.peek();
+            if b == quote {
+                self.pos += usize::from(b.is_some());
+                return Ok(Cow::Owned(result));
+            }
+            match b {
+                Some(b'\\') => {
+                    self.pos += 1;
+                    let Some(escape) = self.peek() else {
+                        return Err(self.error("Unterminated string escape"));
+                    };
+                    match escape {
+                        b'"' => result.push('"'),
+                        b'\'' if self.options.allow_single_quotes => result.push('\''),
+                        b'\\' => result.push('\\'),
+                        b'/' => result.push('/'),
+                        b'b' => result.push('\u{0008}'),
+                        b'f' => result.push('\u{000C}'),
+                        b'n' => result.push('\n'),
+                        b'r' => result.push('\r'),
+                        b't' => result.push('\t'),
+                        b'u' => {
+                            self.pos += 1;
+                            let ch = self.parse_unicode_escape()?;
+                            result.push(ch);
+                            continue;
+                        }
+                        _ => {
+                            let c = self.input[self.pos..].chars().next().unwrap_or_default();
+                            return Err(self.error(format!("Invalid escape sequence: \\{}", c)));
+                        }
+                    }
+                    self.pos += 1;
+                }
+                Some(_) => {
+                    // Copy the whole run of plain characters at once
+                    let run_start = self.pos;
+                    while self.peek().is_some_and(|b| Some(b) != quote && b != b'\\') {
+                        self.pos += 1;
+                    }
+                    result.push_str(&self.input[run_start..self.pos]);
+                }
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+    }
+
+    /// Parse the code point of a `\u` escape, starting just after the `u`
+    ///
+    /// Characters outside the Basic Multilingual Plane are written as a UTF-16
+    /// surrogate pair of two escapes (`\uD83D\uDE00`), which are combined
+    /// here. A surrogate without its other half is rejected.
+    fn parse_unicode_escape(&mut self) -> Result<char, ParseError> {
+        let start = self.pos;
+        let high = self.parse_hex4()?;
+
+        let code = if (0xD800..0xDC00).contains(&high) {
+            let low = if self.bytes[self.pos..].starts_with(b"\\u") {
+                self.pos += 2;
+                self.parse_hex4()?
+            } else {
+                0
+            };
+            if !(0xDC00..0xE000).contains(&low) {
+                self.pos = start;
+                return Err(self.error("Invalid unicode code point"));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+
+        char::from_u32(code).ok_or_else(|| {
+            self.pos = start;
+            self.error("Invalid unicode code point")
+        })
+    }
+
+    /// Parse exactly four hex digits
+    fn parse_hex4(&mut self) -> Result<u32, ParseError> {
+        let digits = match self.bytes.get(self.pos..self.pos + 4) {
+            Some(digits) if digits.iter().all(u8::is_ascii_hexdigit) => digits,
+            _ => return Err(self.error("Invalid unicode escape")),
+        };
+        let code = digits.iter().fold(0, |code, &d| {
+            code * 16 + (d as char).to_digit(16).unwrap_or(0)
+        });
+        self.pos += 4;
+        Ok(code)
+    }
+
+    /// Parse an object key and the ':' that follows it.
+    fn parse_object_key(&mut self) -> Result<Cow<'a, str>, ParseError> {
+        self.skip_whitespace();
+        let key_start = self.pos;
+
+        // Parse key (must be a string unless unquoted keys are allowed)
+        let key = match self.peek() {
+            _ if self.at_string() => self.parse_string()?,
+            Some(b) if is_identifier_start(b) => {
+                let start = self.pos;
+                while self.peek().is_some_and(is_identifier_char) {
+                    self.pos += 1;
+                }
+                let identifier = &self.input[start..self.pos];
+
+                if !self.options.allow_unquoted_keys {
+                    let message = format!(
+                        "Expected string key in object, found unquoted key '{}'",
+                        identifier
+                    );
+                    return Err(ParseError::new(message, self.input, start));
+                }
+                Cow::Borrowed(identifier)
+            }
+            Some(_) => {
+                let c = self.input[self.pos..].chars().next().unwrap_or_default();
+                let message = format!("Expected string key in object, found '{}'", c);
+                return Err(self.error(message));
+            }
+            None => {
+                return Err(self.error("Expected string key in object, found end of input"));
+            }
+        };
+
+        if let Some(limit) = self.options.max_key_length {
+            if key.len() > limit {
+                let message = format!("Key too long: {} bytes, limit is {}", key.len(), limit);
+                return Err(ParseError::with_kind(
+                    ParseErrorKind::KeyTooLong,
+                    message,
+                    self.input,
+                    key_start,
+                ));
+            }
+        }
+
+        self.skip_whitespace();
+
+        // Expect colon
+        if self.peek() != Some(b':') {
+            return Err(self.error("Expected ':' after object key"));
+        }
+        self.pos += 1;
+
+        Ok(key)
+    }
+
+    /// Consume the ',' or ']' after an array element. Returns `true` once the
+    /// array is closed.
+    fn parse_array_separator(&mut self) -> Result<bool, ParseError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(b',') => {
+                self.pos += 1;
+                self.skip_whitespace();
+                Ok(self.options.allow_trailing_commas && self.consume_literal("]"))
+            }
+            Some(b']') => {
+                self.pos += 1;
+                Ok(true)
+            }
+            _ => Err(self.error("Expected ',' or ']' in array")),
+        }
+    }
+
+    /// Consume the ',' or '}' after an object member. Returns `true` once the
+    /// object is closed.
+    fn parse_object_separator(&mut self) -> Result<bool, ParseError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(b',') => {
+                self.pos += 1;
+                self.skip_whitespace();
+                Ok(self.options.allow_trailing_commas && self.consume_literal("}"))
+            }
+            Some(b'}') => {
+                self.pos += 1;
+                Ok(true)
+            }
+            _ => Err(self.error("Expected ',' or '}' in object")),
+        }
+    }
+
+    /// Skip whitespace, and comments too when they are allowed
+    fn skip_whitespace(&mut self) {
+        loop {
+            while let Some(b) = self.peek() {
+                if matches!(b, b' ' | b'\t' | b'\n' | b'\r') {
+                    self.pos += 1;
+                } else if self.options.allow_unicode_whitespace {
+                    match self.input[self.pos..].chars().next() {
+                        Some(c) if c.is_whitespace() => self.pos += c.len_utf8(),
+                        _ => break,
+                    }
+                } else {
+                    break;
+                }
+            }
+
+            if !(self.options.allow_comments && self.skip_comment()) {
+                return;
+            }
+        }
+    }
+
+    /// Skip a comment at the current position, returning whether there was
+    /// one. An unterminated block comment is left in place, so the error
+    /// that follows points at its start.
+    fn skip_comment(&mut self) -> bool {
+        let rest = &self.input[self.pos..];
+        if rest.starts_with("//") {
+            self.pos += rest.find('\n').unwrap_or(rest.len());
+            true
+        } else if let Some(body) = rest.strip_prefix("/*") {
+            match body.find("*/") {
+                Some(end) => {
+                    self.pos += end + 4;
+                    true
+                }
+                None => false,
+            }
+        } else {
+            false
+        }
+    }
+
+    fn skip_digits(&mut self) {
+        while self.peek_digit() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn peek_digit(&self) -> bool {
+        self.peek().is_some_and(|b| b.is_ascii_digit())
+    }
+
+    fn consume_literal(&mut self, literal: &str) -> bool {
+        if self.bytes[self.pos..].starts_with(literal.as_bytes()) {
+            self.pos += literal.len();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Builds a `Value` tree
+///
+/// Array elements and object members are parsed onto scratch stacks shared by
+/// the whole document and moved out once the container closes, so each
+/// container is allocated exactly once at its final size rather than grown
+/// by repeated reallocation.
+struct ValueBuilder<'a> {
+    input: &'a str,
+    duplicate_keys: DuplicateKeys,
+    elements: Vec<Value>,
+    members: Vec<Member>,
+}
+
+/// An object member waiting on the scratch stack: key, value, and the offset
+/// of the key for duplicate-key errors
+type Member = (String, Value, usize);
+
+impl<'a> ValueBuilder<'a> {
+    fn new(input: &'a str, duplicate_keys: DuplicateKeys) -> Self {
+        Self {
+            input,
+            duplicate_keys,
+            elements: Vec::new(),
+            members: Vec::new(),
+        }
+    }
+
+    /// Build an object map from parsed members, applying the duplicate key
+    /// policy
+    fn build_object(
+        &self,
+        members: impl ExactSizeIterator<Item = Member>,
+    ) -> Result<HashMap<String, Value>, ParseError> {
+        let mut object = HashMap::with_capacity(members.len());
+
+        // Keys whose values have already been gathered into an array
+        let mut collected = HashSet::new();
+
+        for (key, value, key_start) in members {
+            match self.duplicate_keys {
+                DuplicateKeys::LastWins => {
+                    object.insert(key, value);
+                }
+                DuplicateKeys::Error => {
+                    if object.contains_key(&key) {
+                        let message = format!("Duplicate key '{}' in object", key);
+                        return Err(ParseError::new(message, self.input, key_start));
+                    }
+                    object.insert(key, value);
+                }
+                DuplicateKeys::Collect => match object.entry(key) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(value);
+                    }
+                    Entry::Occupied(mut entry) => {
+                        if collected.contains(entry.key()) {
+                            if let Value::Array(values) = entry.get_mut() {
+                                values.push(value);
+                            }
+                        } else {
+                            collected.insert(entry.key().clone());
+                            let first = std::mem::replace(entry.get_mut(), Value::Null);
+                            *entry.get_mut() = Value::Array(vec![first, value]);
+                        }
+                    }
+                },
+            }
+        }
+
+        Ok(object)
+    }
+}
+
+impl<'a> Builder<'a> for ValueBuilder<'_> {
+    type Node = Value;
+    type Key = (String, usize);
+
+    fn null(&mut self) -> Value {
+        Value::Null
+    }
+
+    fn boolean(&mut self, b: bool) -> Value {
+        Value::Boolean(b)
+    }
+
+    fn number(&mut self, n: f64, _source: &'a str) -> Value {
+        Value::Number(n)
+    }
+
+    fn string(&mut self, s: Cow<'a, str>) -> Value {
+        Value::String(s.into_owned())
+    }
+
+    fn start_array(&mut self) -> usize {
+        self.elements.len()
+    }
+
+    fn push_element(&mut self, element: Value) {
+        self.elements.push(element);
+    }
+
+    fn finish_array(&mut self, mark: usize) -> Value {
+        Value::Array(self.elements.drain(mark..).collect())
+    }
+
+    fn start_object(&mut self) -> usize {
+        self.members.len()
+    }
+
+    fn key(&mut self, key: Cow<'a, str>, offset: usize) -> (String, usize) {
+        (key.into_owned(), offset)
+    }
+
+    fn push_member(&mut self, (key, key_start): (String, usize), value: Value) {
+        self.members.push((key, value, key_start));
+    }
+
+    fn finish_object(&mut self, mark: usize) -> Result<Value, ParseError> {
+        let mut members = std::mem::take(&mut self.members);
+        let object = self.build_object(members.drain(mark..));
+        self.members = members;
+        object.map(Value::Object)
+    }
+}
+
+/// Builds nothing, for checking syntax while skipping a value
+struct SkipBuilder;
+
+impl<'a> Builder<'a> for SkipBuilder {
+    type Node = ();
+    type Key = ();
+
+    fn null(&mut self) {}
+    fn boolean(&mut self, _: bool) {}
+    fn number(&mut self, _: f64, _: &'a str) {}
+    fn string(&mut self, _: Cow<'a, str>) {}
+
+    fn start_array(&mut self) -> usize {
+        0
+    }
+    fn push_element(&mut self, _: ()) {}
+    fn finish_array(&mut self, _: usize) {}
+
+    fn start_object(&mut self) -> usize {
+        0
+    }
+    fn key(&mut self, _: Cow<'a, str>, _: usize) {}
+    fn push_member(&mut self, _: (), _: ()) {}
+    fn finish_object(&mut self, _: usize) -> Result<(), ParseError> {
+        Ok(())
+    }
+}
+
+fn is_identifier_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_' || b == b'$'
+}
+
+fn is_identifier_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b == b'$'
+}
+
+/// Decode the escape sequences in the contents of a JSON string literal
+///
+/// `s` is the text between the quotes. Escapes are handled exactly as when
+/// parsing, surrogate pairs included; any other character, even a `"`, is
+/// kept as-is. Errors give offsets into `s`.
+pub fn unescape_json_string(s: &str) -> Result<String, ParseError> {
+    Parser::new(s)
+        .parse_string_contents(None)
+        .map(Cow::into_owned)
+}
+
+/// Read a JSON Pointer token as an array index: `0` or digits without a
+/// leading zero
+pub(crate) fn array_index(token: &str) -> Option<usize> {
+    if token.is_empty()
+        || !token.bytes().all(|b| b.is_ascii_digit())
+        || (token.len() > 1 && token.starts_with('0'))
+    {
+        return None;
+    }
+    token.parse().ok()
+}
@@ -0,0 +1,435 @@
+use crate::parser::{array_index, Parser};
+use crate::{ParseError, Value};
+
+/// Split a JSON Pointer (RFC 6901) into its unescaped reference tokens
+///
+/// `""` is the whole document and yields no tokens. Any other pointer must
+/// start with `/`; `~1` decodes to `/` and `~0` to `~`. Returns `None` for
+/// malformed pointers.
+pub(crate) fn parse_pointer(ptr: &str) -> Option<Vec<String>> {
+    if ptr.is_empty() {
+        return Some(Vec::new());
+    }
+
+    ptr.strip_prefix('/')?
+        .split('/')
+        .map(unescape_token)
+        .collect()
+}
+
+/// Append `token` to the pointer `path` as a new reference token, escaping
+/// `~` and `/`
+pub(crate) fn push_token(path: &mut String, token: &str) {
+    path.push('/');
+    for c in token.chars() {
+        match c {
+            '~' => path.push_str("~0"),
+            '/' => path.push_str("~1"),
+            c => path.push(c),
+        }
+    }
+}
+
+/// One step of a path for `Value::get_chain`
+///
+/// Unlike a JSON Pointer token, a segment says whether it names an object
+/// member or an array element, so `Key("0")` only matches a member named
+/// `"0"` and `Index(0)` only matches the first element of an array.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a> {
+    /// Member of an object
+    Key(&'a str),
+    /// Element of an array
+    Index(usize),
+}
+
+fn unescape_token(token: &str) -> Option<String> {
+    let mut result = String::with_capacity(token.len());
+    let mut chars = token.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '~' {
+            match chars.next() {
+                Some('0') => result.push('~'),
+                Some('1') => result.push('/'),
+                _ => return None,
+            }
+        } else {
+            result.push(c);
+        }
+    }
+
+    Some(result)
+}
+
+impl Value {
+    /// Look up a value by JSON Pointer (RFC 6901), e.g. `/users/0/name`
+    ///
+    /// `""` refers to the whole document. Returns `None` if the pointer is
+    /// malformed or does not resolve.
+    pub fn pointer(&self, ptr: &str) -> Option<&Value> {
+        parse_pointer(ptr)?
+            .iter()
+            .try_fold(self, |value, token| match value {
+                Value::Object(object) => object.get(token),
+                Value::Array(elements) => elements.get(array_index(token)?),
+                _ => None,
+            })
+    }
+
+    /// Mutable version of `pointer`, for editing the value at `ptr` in place
+    pub fn pointer_mut(&mut self, ptr: &str) -> Option<&mut Value> {
+        self.descend_mut(&parse_pointer(ptr)?)
+    }
+
+    /// Follow already-parsed pointer tokens down to a value
+    fn descend_mut(&mut self, tokens: &[String]) -> Option<&mut Value> {
+        tokens.iter().try_fold(self, |value, token| match value {
+            Value::Object(object) => object.get_mut(token),
+            Value::Array(elements) => elements.get_mut(array_index(token)?),
+            _ => None,
+        })
+    }
+
+    /// Remove the value at `ptr` and return it
+    ///
+    /// An object member is deleted and an array element is removed, shifting
+    /// the elements after it down by one. This is the JSON Patch `remove`
+    /// operation, and the first half of `move`. Returns `None`, changing
+    /// nothing, when the pointer is malformed or does not resolve. The empty
+    /// pointer takes the whole document and leaves `Null` behind.
+    pub fn take_path(&mut self, ptr: &str) -> Option<Value> {
+        let tokens = parse_pointer(ptr)?;
+        let Some((last, parents)) = tokens.split_last() else {
+            return Some(std::mem::replace(self, Value::Null));
+        };
+
+        match self.descend_mut(parents)? {
+            Value::Object(object) => object.remove(last),
+            Value::Array(elements) => {
+                let index = array_index(last).filter(|&i| i < elements.len())?;
+                Some(elements.remove(index))
+            }
+            _ => None,
+        }
+    }
+
+    /// Follow `segments` from this value, one step at a time
+    ///
+    /// Returns `None` as soon as a step does not apply: a key on anything
+    /// but an object, an index on anything but an array, or a member or
+    /// element that is not there. No segments gives `self`.
+    pub fn get_chain(&self, segments: &[Segment]) -> Option<&Value> {
+        segments
+            .iter()
+            .try_fold(self, |value, segment| match (value, segment) {
+                (Value::Object(object), Segment::Key(key)) => object.get(*key),
+                (Value::Array(elements), Segment::Index(index)) => elements.get(*index),
+                _ => None,
+            })
+    }
+
+    /// Check whether `ptr` resolves to a value, which may itself be `null`
+    pub fn path_exists(&self, ptr: &str) -> bool {
+        self.pointer(ptr).is_some()
+    }
+
+    /// Check whether the value at `ptr` equals `expected`
+    ///
+    /// This is the JSON Patch `test` operation on its own, handy for
+    /// optimistic-concurrency checks. An unresolvable pointer is never equal.
+    pub fn pointer_eq(&self, ptr: &str, expected: &Value) -> bool {
+        self.pointer(ptr) == Some(expected)
+    }
+
+    /// Put `new` at `ptr` and return the value it replaced
+    ///
+    /// This is the JSON Patch `replace` operation: the path must already
+    /// exist, so nothing changes and `None` is returned when it does not
+    /// resolve.
+    pub fn replace_path(&mut self, ptr: &str, new: Value) -> Option<Value> {
+        self.pointer_mut(ptr)
+            .map(|target| std::mem::replace(target, new))
+    }
+}
+
+/// Parse only the value at a JSON Pointer out of a larger document
+///
+/// Subtrees that are not on the path are skipped without being built, so
+/// pulling one field out of a large document costs little more than scanning
+/// up to it. Returns `Ok(None)` if the pointer is malformed or the path does
+/// not exist.
+///
+/// The input is only read as far as the target value and the rest of each
+/// object on the path: syntax errors there are reported, but anything
+/// further on is not checked. Where an object repeats a key on the path, the
+/// last occurrence is followed, matching `Value::from_str`.
+pub fn extract_pointer(input: &str, ptr: &str) -> Result<Option<Value>, ParseError> {
+    match parse_pointer(ptr) {
+        Some(tokens) => Parser::new(input).extract(&tokens),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = r#"{
+        "skip": {"big": [1, 2, {"x": "y\"z"}], "s": "é"},
+        "data": {
+            "items": [
+                {"id": 1, "tags": []},
+                {"id": 2, "tags": ["a", {"deep": {"field": [true, "found"]}}]}
+            ],
+            "a/b": 1,
+            "m~n": 2
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_pointer() {
+        assert_eq!(parse_pointer(""), Some(vec![]));
+        assert_eq!(parse_pointer("/"), Some(vec!["".to_string()]));
+        assert_eq!(
+            parse_pointer("/a~1b/m~0n/0"),
+            Some(vec!["a/b".to_string(), "m~n".to_string(), "0".to_string()])
+        );
+        assert_eq!(parse_pointer("a"), None);
+        assert_eq!(parse_pointer("/a~2"), None);
+    }
+
+    #[test]
+    fn test_pointer() {
+        let value = Value::from_str(DOC).unwrap();
+        assert_eq!(
+            value.pointer("/data/items/1/tags/0"),
+            Some(&Value::String("a".to_string()))
+        );
+        assert_eq!(value.pointer("/data/a~1b"), Some(&Value::Number(1.0)));
+        assert_eq!(value.pointer(""), Some(&value));
+        assert_eq!(value.pointer("/data/items/01"), None);
+        assert_eq!(value.pointer("/data/items/0/id/x"), None);
+        assert_eq!(value.pointer("data"), None);
+    }
+
+    #[test]
+    fn test_pointer_mut() {
+        let mut value = Value::from_str(DOC).unwrap();
+        *value.pointer_mut("/data/items/0/id").unwrap() = Value::Number(10.0);
+        value
+            .pointer_mut("/data/items/1/tags")
+            .and_then(|tags| tags.as_array_mut())
+            .unwrap()
+            .push(Value::Null);
+
+        assert_eq!(
+            value.pointer("/data/items/0/id"),
+            Some(&Value::Number(10.0))
+        );
+        assert_eq!(value.pointer("/data/items/1/tags/2"), Some(&Value::Null));
+        assert!(value.pointer_mut("/data/items/5").is_none());
+        assert!(value.pointer_mut("data").is_none());
+    }
+
+    #[test]
+    fn test_take_path_object_field() {
+        let mut value = Value::from_str(DOC).unwrap();
+        assert_eq!(value.take_path("/data/a~1b"), Some(Value::Number(1.0)));
+        assert!(!value.path_exists("/data/a~1b"));
+        assert!(value.path_exists("/data/m~0n"));
+
+        let items = value.take_path("/data/items").unwrap();
+        assert_eq!(items.as_array().map(Vec::len), Some(2));
+        assert_eq!(
+            value.pointer("/data"),
+            Some(&Value::from_str(r#"{"m~n": 2}"#).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_take_path_array_element() {
+        let mut value = Value::from_str(r#"{"list": [10, 20, 30]}"#).unwrap();
+        assert_eq!(value.take_path("/list/0"), Some(Value::Number(10.0)));
+        assert_eq!(value, Value::from_str(r#"{"list": [20, 30]}"#).unwrap());
+        assert_eq!(value.take_path("/list/1"), Some(Value::Number(30.0)));
+        assert_eq!(value, Value::from_str(r#"{"list": [20]}"#).unwrap());
+    }
+
+    #[test]
+    fn test_take_path_missing_and_root() {
+        let mut value = Value::from_str(r#"{"list": [1], "s": "x"}"#).unwrap();
+        for ptr in ["/list/1", "/list/-", "/list/01", "/missing", "/s/0", "list"] {
+            assert_eq!(value.take_path(ptr), None, "{}", ptr);
+        }
+        assert_eq!(
+            value,
+            Value::from_str(r#"{"list": [1], "s": "x"}"#).unwrap()
+        );
+
+        let whole = value.take_path("").unwrap();
+        assert_eq!(
+            whole,
+            Value::from_str(r#"{"list": [1], "s": "x"}"#).unwrap()
+        );
+        assert_eq!(value, Value::Null);
+    }
+
+    #[test]
+    fn test_get_chain() {
+        use Segment::{Index, Key};
+
+        let value = Value::from_str(DOC).unwrap();
+        assert_eq!(
+            value.get_chain(&[Key("data"), Key("items"), Index(1), Key("tags"), Index(0)]),
+            Some(&Value::String("a".to_string()))
+        );
+        assert_eq!(
+            value.get_chain(&[Key("data"), Key("a/b")]),
+            Some(&Value::Number(1.0))
+        );
+        assert_eq!(value.get_chain(&[]), Some(&value));
+
+        assert_eq!(
+            value.get_chain(&[Key("data"), Key("items"), Index(2)]),
+            None
+        );
+        assert_eq!(
+            value.get_chain(&[Key("data"), Key("items"), Key("0")]),
+            None
+        );
+        assert_eq!(value.get_chain(&[Key("data"), Key("missing")]), None);
+        assert_eq!(value.get_chain(&[Index(0)]), None);
+
+        let numeric_keys = Value::from_str(r#"{"0": "member"}"#).unwrap();
+        assert_eq!(
+            numeric_keys.get_chain(&[Key("0")]),
+            Some(&Value::String("member".to_string()))
+        );
+        assert_eq!(numeric_keys.get_chain(&[Index(0)]), None);
+    }
+
+    #[test]
+    fn test_path_exists() {
+        let value = Value::from_str(DOC).unwrap();
+        assert!(value.path_exists(""));
+        assert!(value.path_exists("/data/items/1/tags/1/deep/field/0"));
+        assert!(value.path_exists("/data/m~0n"));
+        assert!(Value::from_str(r#"{"a": null}"#).unwrap().path_exists("/a"));
+
+        assert!(!value.path_exists("/data/items/2"));
+        assert!(!value.path_exists("/data/items/-"));
+        assert!(!value.path_exists("/data/missing"));
+        assert!(!value.path_exists("data"));
+    }
+
+    #[test]
+    fn test_pointer_eq() {
+        let value = Value::from_str(DOC).unwrap();
+        assert!(value.pointer_eq("/data/items/0/id", &Value::Number(1.0)));
+        assert!(value.pointer_eq("/data/items/0/tags", &Value::Array(vec![])));
+        assert!(!value.pointer_eq("/data/items/0/id", &Value::Number(2.0)));
+        assert!(!value.pointer_eq("/data/missing", &Value::Null));
+        assert!(!value.pointer_eq("/data/items/9", &Value::Null));
+    }
+
+    #[test]
+    fn test_replace_path() {
+        let mut value = Value::from_str(DOC).unwrap();
+        assert_eq!(
+            value.replace_path("/data/items/0/id", Value::String("one".to_string())),
+            Some(Value::Number(1.0))
+        );
+        assert_eq!(
+            value.pointer("/data/items/0/id"),
+            Some(&Value::String("one".to_string()))
+        );
+
+        let old = value.replace_path("", Value::Null).unwrap();
+        assert_eq!(
+            old.pointer("/data/items/0/id"),
+            Some(&Value::String("one".to_string()))
+        );
+        assert_eq!(value, Value::Null);
+    }
+
+    #[test]
+    fn test_replace_path_missing() {
+        let mut value = Value::from_str(DOC).unwrap();
+        assert_eq!(value.replace_path("/data/items/9", Value::Null), None);
+        assert_eq!(value.replace_path("/data/new", Value::Null), None);
+        assert_eq!(value.replace_path("data", Value::Null), None);
+        assert_eq!(value, Value::from_str(DOC).unwrap());
+    }
+
+    #[test]
+    fn test_extract_deep_field() {
+        assert_eq!(
+            extract_pointer(DOC, "/data/items/1/tags/1/deep/field/1").unwrap(),
+            Some(Value::String("found".to_string()))
+        );
+        assert_eq!(
+            extract_pointer(DOC, "/data/items/0").unwrap(),
+            Some(Value::from_str(r#"{"id": 1, "tags": []}"#).unwrap())
+        );
+        assert_eq!(
+            extract_pointer(DOC, "/data/a~1b").unwrap(),
+            Some(Value::Number(1.0))
+        );
+        assert_eq!(
+            extract_pointer(DOC, "/data/m~0n").unwrap(),
+            Some(Value::Number(2.0))
+        );
+        assert_eq!(
+            extract_pointer(DOC, "").unwrap(),
+            Some(Value::from_str(DOC).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_extract_missing_path() {
+        for ptr in [
+            "/nope",
+            "/data/items/2",
+            "/data/items/01",
+            "/data/items/-",
+            "/data/items/0/id/x",
+            "no-slash",
+        ] {
+            assert_eq!(extract_pointer(DOC, ptr).unwrap(), None, "{ptr}");
+        }
+    }
+
+    #[test]
+    fn test_extract_reports_errors_before_target() {
+        let err = extract_pointer(r#"{"a": [1, 2 3], "b": 4}"#, "/b").unwrap_err();
+        assert_eq!(err.message, "Expected ',' or ']' in array");
+
+        // Array elements after the target are not read, but the rest of an
+        // object on the path is, to find repeated keys
+        assert_eq!(
+            extract_pointer(r#"[{"a": 1}, oops"#, "/0/a").unwrap(),
+            Some(Value::Number(1.0))
+        );
+        assert!(extract_pointer(r#"{"a": 1, "b": oops}"#, "/a").is_err());
+    }
+
+    #[test]
+    fn test_extract_duplicate_keys_last_wins() {
+        let cases = [
+            (r#"{"a": 1, "a": 2}"#, "/a"),
+            (r#"{"a": {"b": 1}, "c": 0, "a": {"b": 2}}"#, "/a/b"),
+            (r#"{"a": {"b": 1}, "a": {"c": 2}}"#, "/a/b"),
+            (r#"[{"k": [0], "k": [1, 2]}]"#, "/0/k/1"),
+        ];
+        for (input, ptr) in cases {
+            assert_eq!(
+                extract_pointer(input, ptr).unwrap().as_ref(),
+                Value::from_str(input).unwrap().pointer(ptr),
+                "{} {}",
+                input,
+                ptr
+            );
+        }
+    }
+}
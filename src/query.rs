@@ -0,0 +1,321 @@
+use crate::Value;
+
+/// One step of a JSONPath query
+#[derive(Debug)]
+struct Segment {
+    /// `..`: apply the selector to the node and all of its descendants
+    descendant: bool,
+    selector: Selector,
+}
+
+#[derive(Debug)]
+enum Selector {
+    Key(String),
+    /// Negative indices count from the end of the array
+    Index(i64),
+    Wildcard,
+}
+
+impl Value {
+    /// Run a JSONPath query and return every match
+    ///
+    /// Supported syntax is the core subset:
+    ///
+    /// - `$` the root, which every path must start with
+    /// - `.key` and `['key']` (or `["key"]`) object members
+    /// - `[0]` array elements, with `[-1]` counting from the end
+    /// - `.*` and `[*]` every element or member value
+    /// - `..` recursive descent, e.g. `$..name` or `$..[0]`
+    ///
+    /// Filters (`[?(...)]`), slices and unions are not supported and are
+    /// reported as errors. Matches come out depth first in document order;
+    /// members of one object follow the map's iteration order.
+    pub fn query(&self, path: &str) -> Result<Vec<&Value>, String> {
+        let segments = parse_path(path)?;
+        let mut current = vec![self];
+
+        for segment in &segments {
+            let mut next = Vec::new();
+            for node in current {
+                if segment.descendant {
+                    for descendant in node.select(|_| true) {
+                        apply_selector(descendant, &segment.selector, &mut next);
+                    }
+                } else {
+                    apply_selector(node, &segment.selector, &mut next);
+                }
+            }
+            current = next;
+        }
+
+        Ok(current)
+    }
+}
+
+fn apply_selector<'a>(node: &'a Value, selector: &Selector, out: &mut Vec<&'a Value>) {
+    match (selector, node) {
+        (Selector::Key(key), Value::Object(object)) => out.extend(object.get(key)),
+        (Selector::Index(index), Value::Array(elements)) => {
+            let index = if *index < 0 {
+                elements.len() as i64 + index
+            } else {
+                *index
+            };
+            if let Ok(index) = usize::try_from(index) {
+                out.extend(elements.get(index));
+            }
+        }
+        (Selector::Wildcard, Value::Array(elements)) => out.extend(elements.iter()),
+        (Selector::Wildcard, Value::Object(object)) => out.extend(object.values()),
+        _ => {}
+    }
+}
+
+fn parse_path(path: &str) -> Result<Vec<Segment>, String> {
+    let rest = path
+        .strip_prefix('$')
+        .ok_or_else(|| "JSONPath must start with '$'".to_string())?;
+    let chars: Vec<char> = rest.chars().collect();
+    let mut segments = Vec::new();
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        let mut descendant = false;
+
+        match chars[pos] {
+            '.' => {
+                pos += 1;
+                if chars.get(pos) == Some(&'.') {
+                    descendant = true;
+                    pos += 1;
+                }
+
+                match chars.get(pos) {
+                    Some('[') if descendant => {}
+                    Some('*') => {
+                        pos += 1;
+                        segments.push(Segment {
+                            descendant,
+                            selector: Selector::Wildcard,
+                        });
+                        continue;
+                    }
+                    _ => {
+                        let start = pos;
+                        while pos < chars.len() && !matches!(chars[pos], '.' | '[') {
+                            pos += 1;
+                        }
+                        if start == pos {
+                            return Err(format!("Expected member name at position {}", start + 1));
+                        }
+                        segments.push(Segment {
+                            descendant,
+                            selector: Selector::Key(chars[start..pos].iter().collect()),
+                        });
+                        continue;
+                    }
+                }
+            }
+            '[' => {}
+            c => {
+                return Err(format!(
+                    "Unexpected character '{}' at position {}",
+                    c,
+                    pos + 1
+                ))
+            }
+        }
+
+        // Bracketed selector
+        let (selector, end) = parse_bracket(&chars, pos)?;
+        segments.push(Segment {
+            descendant,
+            selector,
+        });
+        pos = end;
+    }
+
+    Ok(segments)
+}
+
+/// Parse `[...]` starting at `open`, returning the selector and the
+/// position just after the closing bracket
+fn parse_bracket(chars: &[char], open: usize) -> Result<(Selector, usize), String> {
+    let mut pos = open + 1;
+
+    let selector = match chars.get(pos) {
+        Some('*') => {
+            pos += 1;
+            Selector::Wildcard
+        }
+        Some(&quote @ ('\'' | '"')) => {
+            pos += 1;
+            let mut key = String::new();
+            loop {
+                match chars.get(pos) {
+                    None => return Err("Unterminated quoted name".to_string()),
+                    Some('\\') => {
+                        match chars.get(pos + 1) {
+                            Some(&c) => key.push(c),
+                            None => return Err("Unterminated quoted name".to_string()),
+                        }
+                        pos += 2;
+                    }
+                    Some(&c) if c == quote => {
+                        pos += 1;
+                        break;
+                    }
+                    Some(&c) => {
+                        key.push(c);
+                        pos += 1;
+                    }
+                }
+            }
+            Selector::Key(key)
+        }
+        Some('?') => return Err("Filter expressions are not supported".to_string()),
+        _ => {
+            let start = pos;
+            while pos < chars.len() && chars[pos] != ']' {
+                pos += 1;
+            }
+            let text: String = chars[start..pos].iter().collect();
+            let index = text
+                .trim()
+                .parse::<i64>()
+                .map_err(|_| format!("Invalid array index '{}'", text))?;
+            Selector::Index(index)
+        }
+    };
+
+    if chars.get(pos) != Some(&']') {
+        return Err(format!("Expected ']' at position {}", pos + 1));
+    }
+
+    Ok((selector, pos + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = r#"{
+        "store": {
+            "book": [
+                {"title": "Sayings", "price": 8.95, "tags": ["a"]},
+                {"title": "Sword", "price": 12.99},
+                {"title": "Moby Dick", "price": 8.99, "isbn": "0-553"}
+            ],
+            "bicycle": {"color": "red", "price": 19.95}
+        },
+        "odd key": {"it's": true}
+    }"#;
+
+    fn doc() -> Value {
+        Value::from_str(DOC).unwrap()
+    }
+
+    fn sorted_numbers(values: Vec<&Value>) -> Vec<f64> {
+        let mut numbers: Vec<f64> = values.iter().filter_map(|v| v.coerce_number()).collect();
+        numbers.sort_by(f64::total_cmp);
+        numbers
+    }
+
+    fn title(n: &str) -> Value {
+        Value::String(n.to_string())
+    }
+
+    #[test]
+    fn test_root() {
+        let value = doc();
+        assert_eq!(value.query("$").unwrap(), vec![&value]);
+    }
+
+    #[test]
+    fn test_dot_member() {
+        let value = doc();
+        assert_eq!(
+            value.query("$.store.bicycle.color").unwrap(),
+            vec![&title("red")]
+        );
+        assert!(value.query("$.store.missing").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bracket_member() {
+        let value = doc();
+        assert_eq!(
+            value.query("$['store']['bicycle']['color']").unwrap(),
+            vec![&title("red")]
+        );
+        assert_eq!(
+            value.query(r#"$["odd key"]['it\'s']"#).unwrap(),
+            vec![&Value::Boolean(true)]
+        );
+    }
+
+    #[test]
+    fn test_index() {
+        let value = doc();
+        assert_eq!(
+            value.query("$.store.book[1].title").unwrap(),
+            vec![&title("Sword")]
+        );
+        assert_eq!(
+            value.query("$.store.book[-1].title").unwrap(),
+            vec![&title("Moby Dick")]
+        );
+        assert!(value.query("$.store.book[3]").unwrap().is_empty());
+        assert!(value.query("$.store.book[-4]").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_wildcard() {
+        let value = doc();
+        assert_eq!(
+            value.query("$.store.book[*].title").unwrap(),
+            vec![&title("Sayings"), &title("Sword"), &title("Moby Dick")]
+        );
+        assert_eq!(value.query("$.store.*").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_recursive_descent() {
+        let value = doc();
+        assert_eq!(
+            sorted_numbers(value.query("$..price").unwrap()),
+            vec![8.95, 8.99, 12.99, 19.95]
+        );
+        assert_eq!(
+            value.query("$..book[0].title").unwrap(),
+            vec![&title("Sayings")]
+        );
+        assert_eq!(value.query("$..isbn").unwrap(), vec![&title("0-553")]);
+        assert_eq!(value.query("$..[0]").unwrap().len(), 2);
+        // Every node below the root
+        assert_eq!(value.query("$..*").unwrap().len(), 19);
+    }
+
+    #[test]
+    fn test_invalid_paths() {
+        let value = doc();
+        assert_eq!(
+            value.query("store").unwrap_err(),
+            "JSONPath must start with '$'"
+        );
+        assert_eq!(
+            value.query("$.store.book[?(@.price < 10)]").unwrap_err(),
+            "Filter expressions are not supported"
+        );
+        assert_eq!(
+            value.query("$.store[x]").unwrap_err(),
+            "Invalid array index 'x'"
+        );
+        assert_eq!(
+            value.query("$.store['book'").unwrap_err(),
+            "Expected ']' at position 14"
+        );
+        assert!(value.query("$.").is_err());
+        assert!(value.query("$x").is_err());
+    }
+}
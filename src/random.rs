@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use crate::Value;
+
+/// Small deterministic generator (SplitMix64), good enough for building test
+/// corpora without pulling in an RNG crate
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// Characters that exercise the serializer: quotes and backslashes, control
+/// characters, non-ASCII and characters outside the Basic Multilingual Plane
+const INTERESTING_CHARS: &[char] = &[
+    '"',
+    '\\',
+    '/',
+    '\n',
+    '\r',
+    '\t',
+    '\u{0000}',
+    '\u{0008}',
+    '\u{000C}',
+    '\u{001F}',
+    '\u{007F}',
+    'é',
+    'ß',
+    '中',
+    '\u{2028}',
+    '\u{FEFF}',
+    '😀',
+    '\u{10FFFF}',
+];
+
+impl Value {
+    /// Generate an arbitrary value from `seed`, for fuzzing and property
+    /// tests
+    ///
+    /// The same seed always gives the same value. Containers nest at most
+    /// `max_depth` levels below the root. Strings mix plain ASCII with
+    /// characters that need escaping and non-BMP characters; numbers cover
+    /// integers, fractions and extreme exponents but are always finite.
+    pub fn random(max_depth: usize, seed: u64) -> Value {
+        random_value(&mut SplitMix64(seed), max_depth)
+    }
+}
+
+fn random_value(rng: &mut SplitMix64, depth: usize) -> Value {
+    // Only produce containers while there is depth left
+    let kinds = if depth == 0 { 4 } else { 6 };
+
+    match rng.below(kinds) {
+        0 => Value::Null,
+        1 => Value::Boolean(rng.below(2) == 0),
+        2 => Value::Number(random_number(rng)),
+        3 => Value::String(random_string(rng)),
+        4 => {
+            let len = rng.below(5) as usize;
+            Value::Array((0..len).map(|_| random_value(rng, depth - 1)).collect())
+        }
+        _ => {
+            let len = rng.below(5) as usize;
+            let object: HashMap<String, Value> = (0..len)
+                .map(|_| (random_string(rng), random_value(rng, depth - 1)))
+                .collect();
+            Value::Object(object)
+        }
+    }
+}
+
+fn random_number(rng: &mut SplitMix64) -> f64 {
+    match rng.below(4) {
+        0 => rng.below(2000) as f64 - 1000.0,
+        1 => (rng.below(1 << 53) as f64) * if rng.below(2) == 0 { 1.0 } else { -1.0 },
+        2 => rng.below(1_000_000) as f64 / 1000.0,
+        _ => {
+            // Any finite bit pattern, including subnormals and huge exponents
+            loop {
+                let n = f64::from_bits(rng.next());
+                if n.is_finite() {
+                    return n;
+                }
+            }
+        }
+    }
+}
+
+fn random_string(rng: &mut SplitMix64) -> String {
+    let len = rng.below(8);
+    (0..len)
+        .map(|_| {
+            if rng.below(3) == 0 {
+                INTERESTING_CHARS[rng.below(INTERESTING_CHARS.len() as u64) as usize]
+            } else {
+                (b' ' + rng.below(95) as u8) as char
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SerializeOptions;
+
+    #[test]
+    fn test_random_is_deterministic() {
+        assert_eq!(Value::random(3, 42), Value::random(3, 42));
+        assert_ne!(Value::random(3, 1), Value::random(3, 2));
+    }
+
+    #[test]
+    fn test_random_respects_depth() {
+        fn depth(value: &Value) -> usize {
+            match value {
+                Value::Array(elements) => 1 + elements.iter().map(depth).max().unwrap_or(0),
+                Value::Object(object) => 1 + object.values().map(depth).max().unwrap_or(0),
+                _ => 0,
+            }
+        }
+
+        for seed in 0..200 {
+            assert!(depth(&Value::random(2, seed)) <= 2);
+            assert_eq!(depth(&Value::random(0, seed)), 0);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_property() {
+        for seed in 0..2000 {
+            let value = Value::random(4, seed);
+            let json = value.to_string();
+            let parsed = Value::from_str(&json)
+                .unwrap_or_else(|e| panic!("seed {}: {} in {}", seed, e, json));
+            assert_eq!(parsed, value, "seed {}: {}", seed, json);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_property_ascii() {
+        let options = SerializeOptions {
+            ensure_ascii: true,
+            ..Default::default()
+        };
+        for seed in 0..500 {
+            let value = Value::random(4, seed);
+            let json = value.to_string_with_options(&options);
+            assert!(json.is_ascii(), "seed {}: {}", seed, json);
+            assert_eq!(Value::from_str(&json).unwrap(), value, "seed {}", seed);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use crate::Value;
+
+impl Value {
+    /// Infer a rough JSON Schema describing this value, as a starting point
+    /// for writing a real one
+    ///
+    /// The rules are:
+    ///
+    /// - scalars get `{"type": ...}` with `null`, `boolean`, `string`, and
+    ///   `integer` for whole numbers or `number` otherwise
+    /// - objects get `"type": "object"`, a schema under `properties` for each
+    ///   member, and every key listed (sorted) in `required`
+    /// - arrays get `"type": "array"` and an `items` schema. If all elements
+    ///   infer the same schema it is used directly; otherwise `items` is an
+    ///   `anyOf` of the distinct schemas in order of first appearance, so
+    ///   `[1, "a", 2]` gives integer, then string. Empty arrays have no
+    ///   `items`.
+    ///
+    /// Objects in an array are not merged, so records with different keys
+    /// each contribute their own `anyOf` entry.
+    pub fn infer_schema(&self) -> Value {
+        let mut schema = HashMap::new();
+
+        let type_name = match self {
+            Value::Number(_) if self.is_integer() => "integer",
+            _ => self.type_name(),
+        };
+        schema.insert("type".to_string(), Value::String(type_name.to_string()));
+
+        match self {
+            Value::Array(elements) => {
+                let mut distinct: Vec<Value> = Vec::new();
+                for element in elements {
+                    let element_schema = element.infer_schema();
+                    if !distinct.contains(&element_schema) {
+                        distinct.push(element_schema);
+                    }
+                }
+
+                let items = match distinct.len() {
+                    0 => None,
+                    1 => distinct.pop(),
+                    _ => Some(Value::object_of([(
+                        "anyOf".to_string(),
+                        Value::Array(distinct),
+                    )])),
+                };
+                if let Some(items) = items {
+                    schema.insert("items".to_string(), items);
+                }
+            }
+            Value::Object(object) => {
+                let properties = object
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.infer_schema()));
+                let mut required: Vec<&String> = object.keys().collect();
+                required.sort();
+
+                schema.insert("properties".to_string(), Value::object_of(properties));
+                schema.insert(
+                    "required".to_string(),
+                    Value::Array(
+                        required
+                            .into_iter()
+                            .map(|key| Value::String(key.clone()))
+                            .collect(),
+                    ),
+                );
+            }
+            _ => {}
+        }
+
+        Value::Object(schema)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema_of(json: &str) -> Value {
+        Value::from_str(json).unwrap().infer_schema()
+    }
+
+    #[test]
+    fn test_scalars() {
+        assert_eq!(
+            schema_of("null"),
+            Value::from_str(r#"{"type": "null"}"#).unwrap()
+        );
+        assert_eq!(
+            schema_of("true"),
+            Value::from_str(r#"{"type": "boolean"}"#).unwrap()
+        );
+        assert_eq!(
+            schema_of("3"),
+            Value::from_str(r#"{"type": "integer"}"#).unwrap()
+        );
+        assert_eq!(
+            schema_of("3.5"),
+            Value::from_str(r#"{"type": "number"}"#).unwrap()
+        );
+        assert_eq!(
+            schema_of(r#""x""#),
+            Value::from_str(r#"{"type": "string"}"#).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_object() {
+        let schema = schema_of(
+            r#"{
+                "id": 42,
+                "name": "Alice",
+                "score": 9.5,
+                "tags": ["a", "b"],
+                "address": {"city": "NYC", "zip": null},
+                "history": []
+            }"#,
+        );
+        let expected = Value::from_str(
+            r#"{
+                "type": "object",
+                "properties": {
+                    "id": {"type": "integer"},
+                    "name": {"type": "string"},
+                    "score": {"type": "number"},
+                    "tags": {"type": "array", "items": {"type": "string"}},
+                    "address": {
+                        "type": "object",
+                        "properties": {
+                            "city": {"type": "string"},
+                            "zip": {"type": "null"}
+                        },
+                        "required": ["city", "zip"]
+                    },
+                    "history": {"type": "array"}
+                },
+                "required": ["address", "history", "id", "name", "score", "tags"]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(schema, expected);
+    }
+
+    #[test]
+    fn test_mixed_array() {
+        assert_eq!(
+            schema_of(r#"[1, "a", 2, null, "b"]"#),
+            Value::from_str(
+                r#"{
+                    "type": "array",
+                    "items": {"anyOf": [
+                        {"type": "integer"},
+                        {"type": "string"},
+                        {"type": "null"}
+                    ]}
+                }"#
+            )
+            .unwrap()
+        );
+    }
+}
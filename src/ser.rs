@@ -0,0 +1,748 @@
+use std::collections::HashSet;
+use std::fmt::{self, Write};
+
+use crate::pointer::push_token;
+use crate::Value;
+
+/// Options controlling how a `Value` is written as JSON
+#[derive(Debug, Clone, Default)]
+pub struct SerializeOptions {
+    /// Write object members in ascending key order instead of the map's
+    /// iteration order, so equal values always serialize identically
+    pub sort_keys: bool,
+    /// Escape every non-ASCII character as `\uXXXX`, so the output is pure
+    /// ASCII
+    pub ensure_ascii: bool,
+    /// Escape `<`, `>` and `&` as `\u003c`, `\u003e` and `\u0026`, so the
+    /// output can be embedded in an HTML `<script>` element without a string
+    /// containing `</script>` ending it early
+    pub escape_html: bool,
+    /// Pretty print with each element and member on its own line, indented
+    /// by this many spaces per level. `None` (the default) writes compact
+    /// JSON on one line.
+    pub indent: Option<usize>,
+    /// Line ending used between lines when pretty printing
+    pub line_ending: LineEnding,
+    /// JSON Pointers of numbers to write as quoted strings, e.g. `"/id"`,
+    /// for clients that would lose precision on large integers. The string
+    /// holds the digits the number would otherwise be written with. Values
+    /// at these paths that are not numbers are written normally.
+    pub cast_numbers_to_strings: HashSet<String>,
+    /// End the output with one line ending (`\n` unless `line_ending` says
+    /// otherwise), as POSIX tools and editors expect of text files
+    pub trailing_newline: bool,
+}
+
+/// Line ending style for pretty-printed output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`, as Windows tools expect
+    CrLf,
+}
+
+impl LineEnding {
+    fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// Formats the value as compact JSON, so `value.to_string()` serializes it
+///
+/// Non-finite numbers have no JSON representation and are written as
+/// `null`.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_with_options(&SerializeOptions::default()))
+    }
+}
+
+impl Value {
+    /// Serialize to JSON using the given options
+    pub fn to_string_with_options(&self, options: &SerializeOptions) -> String {
+        let mut serializer = Serializer::new(self, options);
+        while serializer.step() {}
+        serializer.out
+    }
+
+    /// Serialize to indented, multi-line JSON with two spaces per level
+    pub fn to_string_pretty(&self) -> String {
+        let options = SerializeOptions {
+            indent: Some(2),
+            ..Default::default()
+        };
+        self.to_string_with_options(&options)
+    }
+
+    /// Serialize to indented JSON with object keys sorted at every level,
+    /// using `indent` spaces per level
+    ///
+    /// Equal values always give the same text whatever order their members
+    /// were inserted in, which keeps golden files and snapshots stable.
+    pub fn to_pretty_sorted(&self, indent: usize) -> String {
+        let options = SerializeOptions {
+            sort_keys: true,
+            indent: Some(indent),
+            ..Default::default()
+        };
+        self.to_string_with_options(&options)
+    }
+
+    /// Serialize to the canonical form, as UTF-8 bytes ready for a hasher or
+    /// signer
+    ///
+    /// The canonical form is compact JSON with object keys sorted by their
+    /// UTF-8 bytes (equivalently, by code point) and numbers in their
+    /// shortest round-trip form, so values that compare equal produce
+    /// identical bytes whatever order their members were inserted in.
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        let options = SerializeOptions {
+            sort_keys: true,
+            ..Default::default()
+        };
+        self.to_string_with_options(&options).into_bytes()
+    }
+
+    /// Serialize lazily, yielding the JSON text in pieces of at most
+    /// `chunk_size` bytes
+    ///
+    /// The output is produced as the iterator is advanced rather than all at
+    /// once, so callers can interleave writes with other work and bound
+    /// their buffers. Chunks always end on a character boundary; a
+    /// `chunk_size` too small for the next character still yields that one
+    /// character. Concatenated, the chunks equal `to_string_with_options`.
+    pub fn serialize_chunks<'a>(
+        &'a self,
+        options: &'a SerializeOptions,
+        chunk_size: usize,
+    ) -> SerializeChunks<'a> {
+        SerializeChunks {
+            serializer: Serializer::new(self, options),
+            chunk_size: chunk_size.max(1),
+            start: 0,
+        }
+    }
+}
+
+/// Iterator over the pieces of a value's JSON text, returned by
+/// `Value::serialize_chunks`
+pub struct SerializeChunks<'a> {
+    serializer: Serializer<'a, 'a>,
+    chunk_size: usize,
+    /// Start of the text in the serializer's buffer not yet handed out
+    start: usize,
+}
+
+impl Iterator for SerializeChunks<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        while self.serializer.out.len() - self.start < self.chunk_size {
+            if self.start > 0 {
+                self.serializer.out.drain(..self.start);
+                self.start = 0;
+            }
+            if !self.serializer.step() {
+                break;
+            }
+        }
+
+        let out = &self.serializer.out;
+        let rest = out.get(self.start..).filter(|rest| !rest.is_empty())?;
+        let mut end = self.chunk_size.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end == 0 {
+            end = rest.chars().next().map_or(0, char::len_utf8);
+        }
+
+        let chunk = rest[..end].to_string();
+        self.start += end;
+        Some(chunk)
+    }
+}
+
+/// Writes a value one step at a time, keeping the containers it is inside
+/// on an explicit stack, so output can stop and resume anywhere and deep
+/// nesting cannot overflow the call stack
+struct Serializer<'v, 'o> {
+    out: String,
+    options: &'o SerializeOptions,
+    /// Nesting level of the container being written, for indentation
+    depth: usize,
+    /// JSON Pointer to the value being written, tracked only while
+    /// `cast_numbers_to_strings` is in use
+    path: String,
+    /// Value to write on the next step
+    pending: Option<&'v Value>,
+    /// Containers opened but not yet closed, innermost last
+    stack: Vec<Frame<'v>>,
+    /// Whether the whole value, and any trailing newline, has been written
+    done: bool,
+}
+
+struct Frame<'v> {
+    children: Children<'v>,
+    /// Whether no child has been written yet
+    first: bool,
+    /// Length of `path` at this container, to truncate back to before each
+    /// child
+    path_len: usize,
+}
+
+enum Children<'v> {
+    Elements(std::iter::Enumerate<std::slice::Iter<'v, Value>>),
+    Members(std::vec::IntoIter<(&'v String, &'v Value)>),
+}
+
+impl<'v, 'o> Serializer<'v, 'o> {
+    fn new(value: &'v Value, options: &'o SerializeOptions) -> Self {
+        Serializer {
+            out: String::new(),
+            options,
+            depth: 0,
+            path: String::new(),
+            pending: Some(value),
+            stack: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Write the next value, separator or closing bracket, returning false
+    /// once everything has been written
+    fn step(&mut self) -> bool {
+        if let Some(value) = self.pending.take() {
+            self.open(value);
+            return true;
+        }
+
+        let Some(frame) = self.stack.last_mut() else {
+            if self.done {
+                return false;
+            }
+            self.done = true;
+            if self.options.trailing_newline {
+                self.out.push_str(self.options.line_ending.as_str());
+            }
+            return true;
+        };
+        let child = match &mut frame.children {
+            Children::Elements(elements) => elements.next().map(|(i, element)| (None, i, element)),
+            Children::Members(members) => members.next().map(|(key, value)| (Some(key), 0, value)),
+        };
+        let Some((key, index, value)) = child else {
+            let frame = self.stack.pop().expect("frame was just inspected");
+            self.depth -= 1;
+            if !frame.first {
+                self.newline();
+            }
+            self.out.push(match frame.children {
+                Children::Elements(_) => ']',
+                Children::Members(_) => '}',
+            });
+            return true;
+        };
+        let was_first = std::mem::replace(&mut frame.first, false);
+        let path_len = frame.path_len;
+
+        if !was_first {
+            self.out.push(',');
+        }
+        self.newline();
+        if let Some(key) = key {
+            write_string(&mut self.out, key, self.options);
+            self.out.push_str(if self.options.indent.is_some() {
+                ": "
+            } else {
+                ":"
+            });
+        }
+        if !self.options.cast_numbers_to_strings.is_empty() {
+            self.path.truncate(path_len);
+            match key {
+                Some(key) => push_token(&mut self.path, key),
+                None => push_token(&mut self.path, &index.to_string()),
+            }
+        }
+        self.pending = Some(value);
+        true
+    }
+
+    /// Write a scalar whole, or the opening bracket of a container
+    fn open(&mut self, value: &'v Value) {
+        let children = match value {
+            Value::Null => return self.out.push_str("null"),
+            Value::Boolean(b) => return self.out.push_str(if *b { "true" } else { "false" }),
+            Value::Number(n) if self.options.cast_numbers_to_strings.contains(&self.path) => {
+                self.out.push('"');
+                write_number(&mut self.out, *n);
+                self.out.push('"');
+                return;
+            }
+            Value::Number(n) => return write_number(&mut self.out, *n),
+            Value::String(s) => return write_string(&mut self.out, s, self.options),
+            Value::Array(elements) if elements.is_empty() => return self.out.push_str("[]"),
+            Value::Object(object) if object.is_empty() => return self.out.push_str("{}"),
+            Value::Array(elements) => {
+                self.out.push('[');
+                Children::Elements(elements.iter().enumerate())
+            }
+            Value::Object(object) => {
+                let mut members: Vec<(&String, &Value)> = object.iter().collect();
+                if self.options.sort_keys {
+                    members.sort_unstable_by(|a, b| a.0.cmp(b.0));
+                }
+                self.out.push('{');
+                Children::Members(members.into_iter())
+            }
+        };
+
+        self.depth += 1;
+        self.stack.push(Frame {
+            children,
+            first: true,
+            path_len: self.path.len(),
+        });
+    }
+
+    /// Start a new line at the current depth when pretty printing
+    fn newline(&mut self) {
+        if let Some(indent) = self.options.indent {
+            self.out.push_str(self.options.line_ending.as_str());
+            self.out
+                .extend(std::iter::repeat_n(' ', indent * self.depth));
+        }
+    }
+}
+
+/// Write a number in the shortest form that parses back to the same `f64`
+///
+/// Very large and very small magnitudes use exponent notation (`1e300`)
+/// rather than hundreds of digits, following the same cut-offs as
+/// JavaScript. Negative zero is written as `0`, which it equals.
+pub(crate) fn write_number(out: &mut String, n: f64) {
+    if !n.is_finite() {
+        out.push_str("null");
+        return;
+    }
+    if n == 0.0 {
+        out.push('0');
+        return;
+    }
+
+    if !(1e-7..1e21).contains(&n.abs()) {
+        let _ = write!(out, "{:e}", n);
+    } else {
+        let _ = write!(out, "{}", n);
+    }
+}
+
+/// Escape `s` for use inside a JSON string literal
+///
+/// Applies the same rules as the serializer with default options: `"`, `\`
+/// and control characters are escaped, and everything else, non-ASCII
+/// included, is kept as-is. The result has no surrounding quotes.
+pub fn escape_json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    escape_json_string_into(s, &mut out);
+    out
+}
+
+/// Same as `escape_json_string`, appending to `out` instead of allocating
+pub fn escape_json_string_into(s: &str, out: &mut String) {
+    write_escaped(out, s, &SerializeOptions::default());
+}
+
+/// Write a string literal with surrounding quotes, escaping `"`, `\` and
+/// control characters. With `ensure_ascii`, non-ASCII characters are escaped
+/// too, characters outside the Basic Multilingual Plane as a UTF-16
+/// surrogate pair (`\ud83d\ude00`); with `escape_html`, so are `<`, `>` and
+/// `&`. Everything else is written as-is.
+pub(crate) fn write_string(out: &mut String, s: &str, options: &SerializeOptions) {
+    out.push('"');
+    write_escaped(out, s, options);
+    out.push('"');
+}
+
+/// Write the escaped contents of a string literal, without quotes
+fn write_escaped(out: &mut String, s: &str, options: &SerializeOptions) {
+    let mut run_start = 0;
+    for (i, c) in s.char_indices() {
+        let escape = match c {
+            '"' => "\\\"",
+            '\\' => "\\\\",
+            '\n' => "\\n",
+            '\r' => "\\r",
+            '\t' => "\\t",
+            '\u{0008}' => "\\b",
+            '\u{000C}' => "\\f",
+            c if c < ' ' || (options.ensure_ascii && !c.is_ascii()) => "",
+            '<' | '>' | '&' if options.escape_html => "",
+            _ => continue,
+        };
+
+        out.push_str(&s[run_start..i]);
+        if escape.is_empty() {
+            for unit in c.encode_utf16(&mut [0; 2]) {
+                let _ = write!(out, "\\u{:04x}", unit);
+            }
+        } else {
+            out.push_str(escape);
+        }
+        run_start = i + c.len_utf8();
+    }
+    out.push_str(&s[run_start..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_json_string() {
+        assert_eq!(escape_json_string(r#"say "hi""#), r#"say \"hi\""#);
+        assert_eq!(escape_json_string(r"C:\dir"), r"C:\\dir");
+        assert_eq!(
+            escape_json_string("a\nb\tc\r\u{8}\u{c}\u{0}\u{1f}"),
+            r"a\nb\tc\r\b\f\u0000\u001f"
+        );
+        assert_eq!(escape_json_string("héllo 日本 😀 </>"), "héllo 日本 😀 </>");
+        assert_eq!(escape_json_string(""), "");
+
+        let mut out = "prefix ".to_string();
+        escape_json_string_into("\"x\"", &mut out);
+        assert_eq!(out, r#"prefix \"x\""#);
+
+        let text = "tab\t \"quote\" \\ é";
+        let literal = format!("\"{}\"", escape_json_string(text));
+        assert_eq!(
+            Value::from_str(&literal).unwrap(),
+            Value::String(text.to_string())
+        );
+    }
+
+    #[test]
+    fn test_scalars() {
+        assert_eq!(Value::Null.to_string(), "null");
+        assert_eq!(Value::Boolean(true).to_string(), "true");
+        assert_eq!(Value::Boolean(false).to_string(), "false");
+        assert_eq!(Value::Number(f64::NAN).to_string(), "null");
+        assert_eq!(Value::Number(f64::INFINITY).to_string(), "null");
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(Value::Number(30.0).to_string(), "30");
+        assert_eq!(Value::Number(-0.0).to_string(), "0");
+        assert_eq!(Value::Number(-2.5).to_string(), "-2.5");
+        assert_eq!(Value::Number(0.1).to_string(), "0.1");
+        assert_eq!(Value::Number(1e21).to_string(), "1e21");
+        assert_eq!(Value::Number(1e300).to_string(), "1e300");
+        assert_eq!(Value::Number(1.5e-8).to_string(), "1.5e-8");
+        assert_eq!(Value::Number(123456789.0).to_string(), "123456789");
+    }
+
+    #[test]
+    fn test_numbers_use_shortest_round_trip_form() {
+        let cases = [
+            (0.1 + 0.2, "0.30000000000000004"),
+            (1.0 / 3.0, "0.3333333333333333"),
+            (100.0 * 1.1, "110.00000000000001"),
+            (9007199254740993.0, "9007199254740992"),
+            (5e-324, "5e-324"),
+            (2.2250738585072014e-308, "2.2250738585072014e-308"),
+            (f64::MAX, "1.7976931348623157e308"),
+            (f64::EPSILON, "2.220446049250313e-16"),
+            (1e-7, "0.0000001"),
+            (9.999999999999999e20, "999999999999999900000"),
+        ];
+
+        for (n, expected) in cases {
+            let json = Value::Number(n).to_string();
+            assert_eq!(json, expected);
+            match Value::from_str(&json).unwrap() {
+                Value::Number(parsed) => assert_eq!(parsed.to_bits(), n.to_bits(), "{}", json),
+                other => panic!("{} parsed as {:?}", json, other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        assert_eq!(
+            Value::String("a\"b\\c\nd\te\u{0001}\u{001f}".to_string()).to_string(),
+            r#""a\"b\\c\nd\te\u0001\u001f""#
+        );
+        assert_eq!(
+            Value::String("héllo 😀 /".to_string()).to_string(),
+            "\"héllo 😀 /\""
+        );
+    }
+
+    #[test]
+    fn test_round_trip_corpus() {
+        let corpus = [
+            "30.0",
+            "-0.0",
+            "0.1",
+            "1e21",
+            "123456789012345678",
+            "5e-324",
+            "1.7976931348623157e308",
+            r#""\u0000\u001f\u007f""#,
+            r#""tab\there\r\n""#,
+            r#""\"\\\/\b\f""#,
+            r#""\uD83D\uDE00 😀 \u2028""#,
+            r#""\uDBFF\uDFFF""#,
+            r#"{"nested": {"a": [1, 2.50, {"": null}]}, "b": false}"#,
+            r#"[[], {}, [[]], "", -1E+2]"#,
+        ];
+
+        for input in corpus {
+            let value = Value::from_str(input).unwrap();
+            let json = value.to_string();
+            assert_eq!(
+                Value::from_str(&json).unwrap(),
+                value,
+                "{} -> {}",
+                input,
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn test_ensure_ascii() {
+        let options = SerializeOptions {
+            ensure_ascii: true,
+            ..Default::default()
+        };
+        let value = Value::String("é 中 😀\n".to_string());
+        let json = value.to_string_with_options(&options);
+
+        assert_eq!(json, r#""\u00e9 \u4e2d \ud83d\ude00\n""#);
+        assert!(json.is_ascii());
+        assert_eq!(Value::from_str(&json).unwrap(), value);
+
+        let object = Value::from_str(r#"{"ключ": ["😀"]}"#).unwrap();
+        let json = object.to_string_with_options(&options);
+        assert_eq!(json, r#"{"\u043a\u043b\u044e\u0447":["\ud83d\ude00"]}"#);
+        assert_eq!(Value::from_str(&json).unwrap(), object);
+    }
+
+    #[test]
+    fn test_pretty() {
+        let value = Value::from_str(r#"{"a": [1, [], {}, {"b": null}], "c": "d"}"#).unwrap();
+        let options = SerializeOptions {
+            sort_keys: true,
+            indent: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            value.to_string_with_options(&options),
+            "{\n  \"a\": [\n    1,\n    [],\n    {},\n    {\n      \"b\": null\n    }\n  ],\n  \"c\": \"d\"\n}"
+        );
+        assert_eq!(Value::Number(1.0).to_string_pretty(), "1");
+        assert_eq!(Value::from_str("[]").unwrap().to_string_pretty(), "[]");
+        assert_eq!(Value::from_str(&value.to_string_pretty()).unwrap(), value);
+    }
+
+    #[test]
+    fn test_pretty_sorted_is_stable() {
+        let a =
+            Value::from_str(r#"{"z": 1, "a": {"y": [1, {"d": 2, "c": 3}], "b": null}}"#).unwrap();
+        let b =
+            Value::from_str(r#"{"a": {"b": null, "y": [1, {"c": 3, "d": 2}]}, "z": 1}"#).unwrap();
+
+        let expected = "{\n   \"a\": {\n      \"b\": null,\n      \"y\": [\n         1,\n         {\n            \"c\": 3,\n            \"d\": 2\n         }\n      ]\n   },\n   \"z\": 1\n}";
+        assert_eq!(a.to_pretty_sorted(3), expected);
+        assert_eq!(b.to_pretty_sorted(3), expected);
+        assert_eq!(
+            a.to_pretty_sorted(0),
+            "{\n\"a\": {\n\"b\": null,\n\"y\": [\n1,\n{\n\"c\": 3,\n\"d\": 2\n}\n]\n},\n\"z\": 1\n}"
+        );
+    }
+
+    #[test]
+    fn test_pretty_crlf() {
+        let value = Value::from_str(r#"{"a": 1, "b": [true]}"#).unwrap();
+        let options = SerializeOptions {
+            sort_keys: true,
+            indent: Some(4),
+            line_ending: LineEnding::CrLf,
+            ..Default::default()
+        };
+        let json = value.to_string_with_options(&options);
+        assert_eq!(
+            json,
+            "{\r\n    \"a\": 1,\r\n    \"b\": [\r\n        true\r\n    ]\r\n}"
+        );
+        assert_eq!(json.matches('\n').count(), json.matches("\r\n").count());
+        assert_eq!(Value::from_str(&json).unwrap(), value);
+    }
+
+    #[test]
+    fn test_trailing_newline() {
+        let value = Value::from_str(r#"{"a": [1, 2]}"#).unwrap();
+        let options = SerializeOptions {
+            trailing_newline: true,
+            ..Default::default()
+        };
+        assert_eq!(value.to_string_with_options(&options), "{\"a\":[1,2]}\n");
+        assert_eq!(value.to_string(), r#"{"a":[1,2]}"#);
+
+        let pretty = SerializeOptions {
+            indent: Some(2),
+            trailing_newline: true,
+            ..Default::default()
+        };
+        let text = value.to_string_with_options(&pretty);
+        assert_eq!(text, "{\n  \"a\": [\n    1,\n    2\n  ]\n}\n");
+        assert!(!value.to_string_pretty().ends_with('\n'));
+
+        let crlf = SerializeOptions {
+            line_ending: LineEnding::CrLf,
+            ..pretty
+        };
+        assert_eq!(Value::Null.to_string_with_options(&crlf), "null\r\n");
+        let chunks: String = value.serialize_chunks(&options, 3).collect();
+        assert_eq!(chunks, value.to_string_with_options(&options));
+    }
+
+    #[test]
+    fn test_escape_html() {
+        let options = SerializeOptions {
+            escape_html: true,
+            ..Default::default()
+        };
+        let value =
+            Value::from_str(r#"{"<b>": "</script><script>alert(1 && 2)</script>"}"#).unwrap();
+        let json = value.to_string_with_options(&options);
+
+        assert_eq!(
+            json,
+            r#"{"\u003cb\u003e":"\u003c/script\u003e\u003cscript\u003ealert(1 \u0026\u0026 2)\u003c/script\u003e"}"#
+        );
+        assert!(!json.contains("</"));
+        assert_eq!(Value::from_str(&json).unwrap(), value);
+        assert_eq!(Value::String("a<b".to_string()).to_string(), r#""a<b""#);
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let value = Value::from_str(r#"{"b": 1, "a": {"d": [], "c": null}, "B": 2}"#).unwrap();
+        let options = SerializeOptions {
+            sort_keys: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            value.to_string_with_options(&options),
+            r#"{"B":2,"a":{"c":null,"d":[]},"b":1}"#
+        );
+    }
+
+    #[test]
+    fn test_cast_numbers_to_strings() {
+        let value = Value::from_str(
+            r#"{"id": 12345678901234568, "count": 3, "users": [{"id": 42, "a/b": 1}], "name": "x"}"#,
+        )
+        .unwrap();
+        let options = SerializeOptions {
+            sort_keys: true,
+            cast_numbers_to_strings: ["/id", "/users/0/a~1b", "/name", "/missing"]
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            ..Default::default()
+        };
+        assert_eq!(
+            value.to_string_with_options(&options),
+            r#"{"count":3,"id":"12345678901234568","name":"x","users":[{"a/b":"1","id":42}]}"#
+        );
+    }
+
+    #[test]
+    fn test_canonical_bytes() {
+        let a = Value::from_str(r#"{"id": 7, "tags": ["x", "y"], "meta": {"z": 1.50, "k": -0}}"#)
+            .unwrap();
+        let b = Value::from_str(
+            r#"{
+                "meta": {"k": 0, "z": 15e-1},
+                "tags": ["x", "y"],
+                "id": 7.0
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(a.to_canonical_bytes(), b.to_canonical_bytes());
+        assert_eq!(
+            a.to_canonical_bytes(),
+            br#"{"id":7,"meta":{"k":0,"z":1.5},"tags":["x","y"]}"#
+        );
+    }
+
+    #[test]
+    fn test_serialize_chunks() {
+        let value = Value::from_str(
+            r#"{"name": "Zoë 日本", "list": [1, 2.5, [true, null], {}], "nested": {"a": [], "b": "x"}}"#,
+        )
+        .unwrap();
+        let options = [
+            SerializeOptions::default(),
+            SerializeOptions {
+                sort_keys: true,
+                indent: Some(2),
+                cast_numbers_to_strings: ["/list/1".to_string()].into_iter().collect(),
+                ..Default::default()
+            },
+        ];
+
+        for options in &options {
+            let full = value.to_string_with_options(options);
+            for chunk_size in [0, 1, 2, 3, 7, 64, 4096] {
+                let chunks: Vec<String> = value.serialize_chunks(options, chunk_size).collect();
+                assert_eq!(chunks.concat(), full, "chunk size {}", chunk_size);
+                assert!(chunks.iter().all(|chunk| !chunk.is_empty()));
+                // Only a lone wide character may exceed the limit
+                assert!(chunks
+                    .iter()
+                    .all(|chunk| chunk.len() <= chunk_size.max(1) || chunk.chars().count() == 1));
+            }
+        }
+
+        let chunks: Vec<String> = Value::Number(12345.0)
+            .serialize_chunks(&SerializeOptions::default(), 2)
+            .collect();
+        assert_eq!(chunks, vec!["12", "34", "5"]);
+    }
+
+    #[test]
+    fn test_serialize_deeply_nested() {
+        let mut value = Value::Null;
+        for _ in 0..10_000 {
+            value = Value::Array(vec![value]);
+        }
+        assert_eq!(
+            value.to_string(),
+            format!("{}null{}", "[".repeat(10_000), "]".repeat(10_000))
+        );
+        drop(value);
+    }
+
+    #[test]
+    fn test_containers() {
+        assert_eq!(Value::Array(vec![]).to_string(), "[]");
+        assert_eq!(
+            Value::from_str(r#"[1, [true, null], {"a": "b"}]"#)
+                .unwrap()
+                .to_string(),
+            r#"[1,[true,null],{"a":"b"}]"#
+        );
+    }
+}
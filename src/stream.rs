@@ -0,0 +1,370 @@
+use std::io::{ErrorKind, Read};
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Value;
+
+const READ_CHUNK: usize = 8 * 1024;
+
+/// Parse a stream of JSON values, such as NDJSON logs or concatenated
+/// documents, reading `reader` incrementally
+///
+/// Values may be separated by any JSON whitespace, newlines included, or by
+/// nothing at all where that is unambiguous (`{"a":1}[2]`). Only as much
+/// input is buffered as the value being parsed needs, and a value may span
+/// any number of reads.
+///
+/// Error offsets, lines and columns are positions in the whole stream. The
+/// iterator ends after the first error, since there is no reliable way to
+/// find the start of the next value; I/O failures are reported as
+/// `ParseErrorKind::Io` and invalid UTF-8 as `ParseErrorKind::InvalidUtf8`.
+pub fn parse_stream<R: Read>(reader: R) -> impl Iterator<Item = Result<Value, ParseError>> {
+    StreamValues {
+        reader,
+        buf: Vec::new(),
+        start: 0,
+        eof: false,
+        done: false,
+        offset: 0,
+        line: 1,
+        column: 1,
+    }
+}
+
+struct StreamValues<R> {
+    reader: R,
+    buf: Vec<u8>,
+    /// Start of the unconsumed part of `buf`
+    start: usize,
+    eof: bool,
+    done: bool,
+    /// Stream position of `buf[start]`
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<R: Read> Iterator for StreamValues<R> {
+    type Item = Result<Value, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.next_value();
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+        result
+    }
+}
+
+impl<R: Read> StreamValues<R> {
+    fn next_value(&mut self) -> Option<Result<Value, ParseError>> {
+        // Skip whitespace up to the next value, or the end of the stream
+        loop {
+            let skipped = self.buf[self.start..]
+                .iter()
+                .take_while(|b| matches!(b, b' ' | b'\t' | b'\n' | b'\r'))
+                .count();
+            self.advance(skipped);
+            if self.start < self.buf.len() {
+                break;
+            }
+            if self.eof {
+                return None;
+            }
+            if let Err(e) = self.fill() {
+                return Some(Err(e));
+            }
+        }
+
+        let mut boundary = Boundary::new(self.buf[self.start]);
+        let len = loop {
+            if let Some(len) = boundary.scan(&self.buf[self.start..]) {
+                break len;
+            }
+            if self.eof {
+                // Incomplete; let the parser report what is missing
+                break self.buf.len() - self.start;
+            }
+            if let Err(e) = self.fill() {
+                return Some(Err(e));
+            }
+        };
+
+        let result = match std::str::from_utf8(&self.buf[self.start..self.start + len]) {
+            Ok(text) => Value::from_str(text).map_err(|e| self.relocate(e)),
+            Err(e) => {
+                self.advance(e.valid_up_to());
+                Err(self.error_here(ParseErrorKind::InvalidUtf8, "Invalid UTF-8"))
+            }
+        };
+        if result.is_ok() {
+            self.advance(len);
+        }
+        Some(result)
+    }
+
+    /// Read more input, first dropping what has already been consumed
+    fn fill(&mut self) -> Result<(), ParseError> {
+        self.buf.drain(..self.start);
+        self.start = 0;
+
+        let mut chunk = [0; READ_CHUNK];
+        loop {
+            match self.reader.read(&mut chunk) {
+                Ok(0) => self.eof = true,
+                Ok(n) => self.buf.extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    let position = self.buf.len();
+                    self.advance(position);
+                    return Err(self.error_here(ParseErrorKind::Io, format!("I/O error: {}", e)));
+                }
+            }
+            return Ok(());
+        }
+    }
+
+    /// Consume `len` bytes, keeping the stream position up to date
+    fn advance(&mut self, len: usize) {
+        for &b in &self.buf[self.start..self.start + len] {
+            if b == b'\n' {
+                self.line += 1;
+                self.column = 1;
+            } else if b & 0xC0 != 0x80 {
+                // Count characters, not UTF-8 continuation bytes
+                self.column += 1;
+            }
+        }
+        self.offset += len;
+        self.start += len;
+    }
+
+    fn error_here(&self, kind: ParseErrorKind, message: impl Into<String>) -> ParseError {
+        let mut error = ParseError::with_kind(kind, message, "", 0);
+        error.offset = self.offset;
+        error.line = self.line;
+        error.column = self.column;
+        error
+    }
+
+    /// Turn a position within the current value into a stream position
+    fn relocate(&self, mut error: ParseError) -> ParseError {
+        if error.line == 1 {
+            error.column += self.column - 1;
+        }
+        error.offset += self.offset;
+        error.line += self.line - 1;
+        error
+    }
+}
+
+/// Finds where a value ends without parsing it, so the whole value can be
+/// buffered before handing it to the parser
+enum Boundary {
+    /// An array or object: ends when its brackets balance
+    Container {
+        depth: usize,
+        in_string: bool,
+        escaped: bool,
+        scanned: usize,
+    },
+    /// A string: ends at the closing quote
+    String { escaped: bool, scanned: usize },
+    /// A number or literal: ends at whitespace or punctuation
+    Scalar { scanned: usize },
+}
+
+impl Boundary {
+    fn new(first: u8) -> Self {
+        match first {
+            b'[' | b'{' => Boundary::Container {
+                depth: 0,
+                in_string: false,
+                escaped: false,
+                scanned: 0,
+            },
+            b'"' => Boundary::String {
+                escaped: false,
+                scanned: 1,
+            },
+            // Always take the first byte, so stray punctuation reaches the
+            // parser and gets a proper error
+            _ => Boundary::Scalar { scanned: 1 },
+        }
+    }
+
+    /// Continue scanning `bytes`, which starts at the value and grows
+    /// between calls. Returns the value's length once its end is in view.
+    fn scan(&mut self, bytes: &[u8]) -> Option<usize> {
+        match self {
+            Boundary::Container {
+                depth,
+                in_string,
+                escaped,
+                scanned,
+            } => {
+                while let Some(&b) = bytes.get(*scanned) {
+                    *scanned += 1;
+                    if *in_string {
+                        match b {
+                            _ if *escaped => *escaped = false,
+                            b'\\' => *escaped = true,
+                            b'"' => *in_string = false,
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    match b {
+                        b'"' => *in_string = true,
+                        b'[' | b'{' => *depth += 1,
+                        b']' | b'}' => {
+                            *depth -= 1;
+                            if *depth == 0 {
+                                return Some(*scanned);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                None
+            }
+            Boundary::String { escaped, scanned } => {
+                while let Some(&b) = bytes.get(*scanned) {
+                    *scanned += 1;
+                    match b {
+                        _ if *escaped => *escaped = false,
+                        b'\\' => *escaped = true,
+                        b'"' => return Some(*scanned),
+                        _ => {}
+                    }
+                }
+                None
+            }
+            Boundary::Scalar { scanned } => {
+                while let Some(&b) = bytes.get(*scanned) {
+                    if matches!(
+                        b,
+                        b' ' | b'\t' | b'\n' | b'\r' | b'[' | b'{' | b']' | b'}' | b',' | b'"'
+                    ) {
+                        return Some(*scanned);
+                    }
+                    *scanned += 1;
+                }
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hands out at most `chunk` bytes per read, so values straddle reads
+    struct Trickle<'a> {
+        data: &'a [u8],
+        chunk: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.chunk.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    const STREAM: &str = "{\"event\": \"start\", \"tags\": [\"a]\", \"\\\"}\"]}\n\
+        [1, [2, 3]]\n\
+        \"plain \\\" string\"  42 -1.5e3\ttrue null\n\
+        {\"é\": \"😀\"}[]{}";
+
+    fn expected() -> Vec<Value> {
+        [
+            r#"{"event": "start", "tags": ["a]", "\"}"]}"#,
+            "[1, [2, 3]]",
+            r#""plain \" string""#,
+            "42",
+            "-1.5e3",
+            "true",
+            "null",
+            r#"{"é": "😀"}"#,
+            "[]",
+            "{}",
+        ]
+        .iter()
+        .map(|s| Value::from_str(s).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn test_parse_stream() {
+        let values: Vec<Value> = parse_stream(STREAM.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(values, expected());
+    }
+
+    #[test]
+    fn test_values_span_reads() {
+        for chunk in [1, 2, 3, 7] {
+            let reader = Trickle {
+                data: STREAM.as_bytes(),
+                chunk,
+            };
+            let values: Vec<Value> = parse_stream(reader).collect::<Result<_, _>>().unwrap();
+            assert_eq!(values, expected(), "chunk size {}", chunk);
+        }
+    }
+
+    #[test]
+    fn test_empty_and_whitespace_streams() {
+        assert_eq!(parse_stream("".as_bytes()).count(), 0);
+        assert_eq!(parse_stream(" \n\t\r\n".as_bytes()).count(), 0);
+    }
+
+    #[test]
+    fn test_errors_use_stream_positions() {
+        let mut values = parse_stream("1\n[2,\n 3 4]\n5".as_bytes());
+        assert_eq!(values.next(), Some(Ok(Value::Number(1.0))));
+
+        let err = values.next().unwrap().unwrap_err();
+        assert_eq!(err.message, "Expected ',' or ']' in array");
+        assert_eq!((err.offset, err.line, err.column), (9, 3, 4));
+        assert_eq!(values.next(), None);
+
+        let err = parse_stream(r#"{"a": [1}"#.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::Syntax);
+
+        let err = parse_stream(r#"["a", "b"#.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.message, "Unterminated string");
+    }
+
+    #[test]
+    fn test_invalid_utf8_and_io_errors() {
+        let mut values = parse_stream(&b"1 \"ab\xFF\""[..]);
+        assert_eq!(values.next(), Some(Ok(Value::Number(1.0))));
+        let err = values.next().unwrap().unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidUtf8);
+        assert_eq!(err.offset, 5);
+
+        struct Failing;
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk on fire"))
+            }
+        }
+        let err = parse_stream(Failing).next().unwrap().unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::Io);
+        assert_eq!(err.message, "I/O error: disk on fire");
+    }
+}
@@ -0,0 +1,789 @@
+use crate::pointer::push_token;
+use crate::Value;
+
+/// Which optional steps `Value::normalize_with_options` applies on top of
+/// number normalization
+#[derive(Debug, Clone, Default)]
+pub struct NormalizeOptions {
+    /// Drop object members whose value is `null`
+    pub remove_null_members: bool,
+    /// Drop `null` elements from arrays, as `remove_nulls_from_arrays`
+    pub remove_null_elements: bool,
+}
+
+impl Value {
+    /// Bring the tree into a canonical in-memory form for comparison and
+    /// hashing, with the default `NormalizeOptions`
+    pub fn normalize(&mut self) {
+        self.normalize_with_options(&NormalizeOptions::default());
+    }
+
+    /// Bring the tree into a canonical in-memory form: numbers are always
+    /// normalized as by `normalize_numbers`, and nulls are removed as
+    /// `options` asks
+    ///
+    /// Objects are hash maps and have no key order to sort, so two objects
+    /// with the same members already compare equal; serialize with
+    /// `sort_keys` or `to_canonical_bytes` for text in key order.
+    pub fn normalize_with_options(&mut self, options: &NormalizeOptions) {
+        self.normalize_numbers();
+        if options.remove_null_members {
+            self.remove_null_members();
+        }
+        if options.remove_null_elements {
+            self.remove_nulls_from_arrays();
+        }
+    }
+
+    /// Replace containers nested deeper than `max_depth` with the string
+    /// `"..."`
+    ///
+    /// The root is at depth 0 and each array or object adds one level, so
+    /// `max_depth = 0` keeps the root container but prunes any container
+    /// inside it. Scalars are never replaced; only arrays and objects beyond
+    /// the limit are, along with everything under them.
+    pub fn prune_depth(&mut self, max_depth: usize) {
+        self.prune_depth_at(0, max_depth);
+    }
+
+    /// Canonicalize every number in the document
+    ///
+    /// Negative zero becomes positive zero, so `-0` and `0` serialize and hash
+    /// the same. Numbers are always stored as `f64`, so integral values such
+    /// as `1.0` and `1` are already identical and need no conversion.
+    pub fn normalize_numbers(&mut self) {
+        match self {
+            // -0.0 == 0.0, so this only changes the sign of zero
+            Value::Number(n) if *n == 0.0 => *n = 0.0,
+            Value::Array(elements) => {
+                for element in elements {
+                    element.normalize_numbers();
+                }
+            }
+            Value::Object(object) => {
+                for value in object.values_mut() {
+                    value.normalize_numbers();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Shorten every string value longer than `max_len` characters
+    ///
+    /// Truncated strings keep their first `max_len` characters followed by
+    /// `…`, so they end up `max_len + 1` characters long. Lengths count
+    /// chars, never splitting a UTF-8 sequence. Object keys are left alone.
+    pub fn truncate_strings(&mut self, max_len: usize) {
+        match self {
+            Value::String(s) => {
+                if let Some((end, _)) = s.char_indices().nth(max_len) {
+                    s.truncate(end);
+                    s.push('…');
+                }
+            }
+            Value::Array(elements) => {
+                for element in elements {
+                    element.truncate_strings(max_len);
+                }
+            }
+            Value::Object(object) => {
+                for value in object.values_mut() {
+                    value.truncate_strings(max_len);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Replace every node matching `predicate` with `replacement(node)`
+    ///
+    /// The tree is walked depth first, testing each container before its
+    /// children. A matching node is replaced as a whole and the replacement
+    /// is not examined again, so nothing inside a replaced subtree is
+    /// visited. The root itself can be replaced.
+    pub fn replace_all(
+        &mut self,
+        predicate: impl Fn(&Value) -> bool,
+        replacement: impl Fn(&Value) -> Value,
+    ) {
+        self.replace_all_with(&predicate, &replacement);
+    }
+
+    fn replace_all_with(
+        &mut self,
+        predicate: &impl Fn(&Value) -> bool,
+        replacement: &impl Fn(&Value) -> Value,
+    ) {
+        if predicate(self) {
+            *self = replacement(self);
+            return;
+        }
+
+        match self {
+            Value::Array(elements) => {
+                for element in elements {
+                    element.replace_all_with(predicate, replacement);
+                }
+            }
+            Value::Object(object) => {
+                for value in object.values_mut() {
+                    value.replace_all_with(predicate, replacement);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Call `f` on every scalar in the tree with its JSON Pointer, letting it
+    /// rewrite the scalar in place
+    ///
+    /// Only leaves are visited: arrays and objects, empty ones included, are
+    /// never passed to `f`. A scalar root is visited with the path `""`. If
+    /// `f` turns a leaf into a container, its contents are not visited.
+    pub fn map_values_recursive(&mut self, mut f: impl FnMut(&str, &mut Value)) {
+        self.map_values_at(&mut String::new(), &mut f);
+    }
+
+    fn map_values_at(&mut self, path: &mut String, f: &mut impl FnMut(&str, &mut Value)) {
+        match self {
+            Value::Array(elements) => {
+                for (i, element) in elements.iter_mut().enumerate() {
+                    let len = path.len();
+                    push_token(path, &i.to_string());
+                    element.map_values_at(path, f);
+                    path.truncate(len);
+                }
+            }
+            Value::Object(object) => {
+                for (key, value) in object.iter_mut() {
+                    let len = path.len();
+                    push_token(path, key);
+                    value.map_values_at(path, f);
+                    path.truncate(len);
+                }
+            }
+            _ => f(path, self),
+        }
+    }
+
+    /// Pass the value through `f`, so transformations read as a chain
+    ///
+    /// `value.apply(normalize).apply(redact)` is `redact(normalize(value))`.
+    pub fn apply(self, f: impl FnOnce(Value) -> Value) -> Value {
+        f(self)
+    }
+
+    /// Like `apply`, but only runs `f` on an object; anything else is
+    /// returned unchanged
+    pub fn map_if_object(self, f: impl FnOnce(Value) -> Value) -> Value {
+        match self {
+            Value::Object(_) => f(self),
+            _ => self,
+        }
+    }
+
+    /// Like `apply`, but only runs `f` on an array; anything else is
+    /// returned unchanged
+    pub fn map_if_array(self, f: impl FnOnce(Value) -> Value) -> Value {
+        match self {
+            Value::Array(_) => f(self),
+            _ => self,
+        }
+    }
+
+    /// Remove `Null` elements from every array in the tree
+    ///
+    /// Object members whose value is `null` are left alone; only array
+    /// elements are dropped.
+    pub fn remove_nulls_from_arrays(&mut self) {
+        match self {
+            Value::Array(elements) => {
+                elements.retain(|element| !element.is_null());
+                for element in elements {
+                    element.remove_nulls_from_arrays();
+                }
+            }
+            Value::Object(object) => {
+                for value in object.values_mut() {
+                    value.remove_nulls_from_arrays();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Remove empty arrays and objects from the tree, bottom up
+    ///
+    /// Children are pruned first, so a container left empty once its own
+    /// children are gone is removed as well. The root is never removed: if
+    /// everything under it is pruned it stays an empty array or object
+    /// rather than turning into `Null`.
+    pub fn prune_empty(&mut self) {
+        match self {
+            Value::Array(elements) => {
+                for element in elements.iter_mut() {
+                    element.prune_empty();
+                }
+                elements.retain(|element| !is_empty_container(element));
+            }
+            Value::Object(object) => {
+                for value in object.values_mut() {
+                    value.prune_empty();
+                }
+                object.retain(|_, value| !is_empty_container(value));
+            }
+            _ => {}
+        }
+    }
+
+    fn remove_null_members(&mut self) {
+        match self {
+            Value::Array(elements) => {
+                for element in elements {
+                    element.remove_null_members();
+                }
+            }
+            Value::Object(object) => {
+                object.retain(|_, value| !value.is_null());
+                for value in object.values_mut() {
+                    value.remove_null_members();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Overwrite the value at each JSON Pointer in `pointers` with `mask`
+    ///
+    /// Pointers that are malformed or do not resolve are skipped. The empty
+    /// pointer masks the whole document.
+    pub fn redact_paths(&mut self, pointers: &[&str], mask: Value) {
+        for ptr in pointers {
+            if let Some(target) = self.pointer_mut(ptr) {
+                *target = mask.clone();
+            }
+        }
+    }
+
+    /// Remove repeated elements from an array, keeping the first occurrence
+    /// of each
+    ///
+    /// Elements are compared with `==`, so nested arrays and objects match
+    /// when they are structurally equal. As with `==` everywhere else in the
+    /// crate, `NaN` never equals anything, so `NaN` elements are all kept.
+    /// Does nothing if `self` is not an array.
+    pub fn dedup_array(&mut self) {
+        if let Value::Array(elements) = self {
+            let mut unique: Vec<Value> = Vec::with_capacity(elements.len());
+            for element in elements.drain(..) {
+                if !unique.contains(&element) {
+                    unique.push(element);
+                }
+            }
+            *elements = unique;
+        }
+    }
+
+    /// Reshape an object into a new one following `spec`, a list of
+    /// `(old_key, new_key, transform)` entries
+    ///
+    /// Each member named by an `old_key` is copied to `new_key` with its
+    /// value passed through `transform`; pass `Value::clone` to keep a value
+    /// as it is. Members the spec does not name are dropped, and entries
+    /// whose `old_key` is missing are skipped. If two entries write the same
+    /// `new_key`, the later one wins. Returns `None` if `self` is not an
+    /// object.
+    pub fn transform_object(&self, spec: &[FieldSpec]) -> Option<Value> {
+        let object = self.as_object()?;
+        Some(Value::object_of(spec.iter().filter_map(
+            |(old_key, new_key, transform)| {
+                let value = object.get(*old_key)?;
+                Some((new_key.to_string(), transform(value)))
+            },
+        )))
+    }
+
+    /// Build a new object from the members for which `f` returns
+    /// `Some((new_key, new_value))`, projecting and renaming in one pass
+    ///
+    /// Members for which `f` returns `None` are dropped. If two members map
+    /// to the same key, which one wins follows the map's iteration order, so
+    /// it is unspecified. Returns `None` if `self` is not an object.
+    pub fn filter_map_object(
+        &self,
+        f: impl Fn(&str, &Value) -> Option<(String, Value)>,
+    ) -> Option<Value> {
+        let object = self.as_object()?;
+        Some(Value::object_of(
+            object.iter().filter_map(|(key, value)| f(key, value)),
+        ))
+    }
+
+    fn prune_depth_at(&mut self, depth: usize, max_depth: usize) {
+        if !matches!(self, Value::Array(_) | Value::Object(_)) {
+            return;
+        }
+
+        if depth > max_depth {
+            *self = Value::String("...".to_string());
+            return;
+        }
+
+        match self {
+            Value::Array(elements) => {
+                for element in elements {
+                    element.prune_depth_at(depth + 1, max_depth);
+                }
+            }
+            Value::Object(object) => {
+                for value in object.values_mut() {
+                    value.prune_depth_at(depth + 1, max_depth);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// One `(old_key, new_key, transform)` entry for `transform_object`
+type FieldSpec<'a> = (&'a str, &'a str, fn(&Value) -> Value);
+
+fn is_empty_container(value: &Value) -> bool {
+    match value {
+        Value::Array(elements) => elements.is_empty(),
+        Value::Object(object) => object.is_empty(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let mut first =
+            Value::from_str(r#"{"b": [-0, 1, null], "a": {"z": -0.0, "y": null}}"#).unwrap();
+        let mut second =
+            Value::from_str(r#"{"a": {"y": null, "z": 0}, "b": [0, 1, null]}"#).unwrap();
+        first.normalize();
+        second.normalize();
+
+        let zero = first.pointer("/a/z").unwrap().as_f64().unwrap();
+        assert!(zero.is_sign_positive());
+        assert_eq!(first, second);
+        assert_eq!(first.to_canonical_bytes(), second.to_canonical_bytes());
+        assert_eq!(first.pointer("/a/y"), Some(&Value::Null));
+    }
+
+    #[test]
+    fn test_normalize_removing_nulls() {
+        let mut value =
+            Value::from_str(r#"{"a": null, "b": [null, -0, {"c": null, "d": [null]}]}"#).unwrap();
+        value.normalize_with_options(&NormalizeOptions {
+            remove_null_members: true,
+            remove_null_elements: true,
+        });
+        assert_eq!(
+            value.to_canonical_bytes(),
+            br#"{"b":[0,{"d":[]}]}"#.to_vec()
+        );
+        assert!(value
+            .pointer("/b/0")
+            .unwrap()
+            .as_f64()
+            .unwrap()
+            .is_sign_positive());
+    }
+
+    #[test]
+    fn test_prune_depth() {
+        let mut value =
+            Value::from_str(r#"{"a": 1, "b": {"c": [true, {"d": {"e": null}}]}, "f": []}"#)
+                .unwrap();
+        value.prune_depth(2);
+        assert_eq!(
+            value,
+            Value::from_str(r#"{"a": 1, "b": {"c": [true, "..."]}, "f": []}"#).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_prune_depth_zero_and_scalars() {
+        let mut value = Value::from_str(r#"[1, [2], {"x": 3}]"#).unwrap();
+        value.prune_depth(0);
+        assert_eq!(value, Value::from_str(r#"[1, "...", "..."]"#).unwrap());
+
+        let mut scalar = Value::Number(1.0);
+        scalar.prune_depth(0);
+        assert_eq!(scalar, Value::Number(1.0));
+    }
+
+    #[test]
+    fn test_truncate_strings() {
+        let long = "x".repeat(50);
+        let mut value = Value::Array(vec![
+            Value::Array(vec![Value::String(long)]),
+            Value::String("short".to_string()),
+            Value::String("héllo wörld".to_string()),
+        ]);
+        value.truncate_strings(5);
+        assert_eq!(
+            value,
+            Value::Array(vec![
+                Value::Array(vec![Value::String("xxxxx…".to_string())]),
+                Value::String("short".to_string()),
+                Value::String("héllo…".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_truncate_strings_leaves_keys() {
+        let mut value = Value::from_str(r#"{"a long key": "a long value"}"#).unwrap();
+        value.truncate_strings(3);
+        assert_eq!(value, Value::from_str(r#"{"a long key": "a l…"}"#).unwrap());
+    }
+
+    #[test]
+    fn test_replace_all_masks_secrets() {
+        let mut value = Value::from_str(
+            r#"{"user": {"password": "hunter2", "name": "bob"}, "log": ["hunter2", "ok"]}"#,
+        )
+        .unwrap();
+        let secret = Value::String("hunter2".to_string());
+        value.replace_all(|v| *v == secret, |_| Value::String("***".to_string()));
+        assert_eq!(
+            value,
+            Value::from_str(
+                r#"{"user": {"password": "***", "name": "bob"}, "log": ["***", "ok"]}"#
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_remove_nulls_from_arrays() {
+        let mut value = Value::from_str(
+            r#"{"a": null, "list": [null, 1, null, [null, {"b": null, "c": [null]}]], "d": [null]}"#,
+        )
+        .unwrap();
+        value.remove_nulls_from_arrays();
+        assert_eq!(
+            value,
+            Value::from_str(r#"{"a": null, "list": [1, [{"b": null, "c": []}]], "d": []}"#)
+                .unwrap()
+        );
+
+        let mut scalar = Value::Null;
+        scalar.remove_nulls_from_arrays();
+        assert_eq!(scalar, Value::Null);
+    }
+
+    #[test]
+    fn test_map_values_recursive_masks_secrets() {
+        let mut value = Value::from_str(
+            r#"{
+                "user": {"name": "bob", "secret_token": "abc", "pin": 1234},
+                "secrets": ["s1", "s2", {"note": "n"}],
+                "public": "hello",
+                "empty": []
+            }"#,
+        )
+        .unwrap();
+
+        let mut visited = Vec::new();
+        value.map_values_recursive(|path, leaf| {
+            visited.push(path.to_string());
+            if path.contains("secret") && leaf.as_str().is_some() {
+                *leaf = Value::String("***".to_string());
+            }
+        });
+
+        assert_eq!(
+            value,
+            Value::from_str(
+                r#"{
+                    "user": {"name": "bob", "secret_token": "***", "pin": 1234},
+                    "secrets": ["***", "***", {"note": "***"}],
+                    "public": "hello",
+                    "empty": []
+                }"#
+            )
+            .unwrap()
+        );
+        visited.sort();
+        assert_eq!(
+            visited,
+            vec![
+                "/public",
+                "/secrets/0",
+                "/secrets/1",
+                "/secrets/2/note",
+                "/user/name",
+                "/user/pin",
+                "/user/secret_token",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_map_values_recursive_root_scalar() {
+        let mut value = Value::Number(1.0);
+        value.map_values_recursive(|path, leaf| {
+            assert_eq!(path, "");
+            *leaf = Value::Array(vec![Value::Number(2.0)]);
+        });
+        assert_eq!(value, Value::from_str("[2]").unwrap());
+    }
+
+    #[test]
+    fn test_apply_chain() {
+        fn normalize(mut value: Value) -> Value {
+            value.normalize_numbers();
+            value
+        }
+        fn redact(mut value: Value) -> Value {
+            value.redact_paths(&["/password"], Value::String("***".to_string()));
+            value
+        }
+
+        let value = Value::from_str(r#"{"password": "hunter2", "balance": -0}"#)
+            .unwrap()
+            .apply(normalize)
+            .apply(redact);
+        let object = value.as_object().unwrap();
+        assert_eq!(object["password"], Value::String("***".to_string()));
+        // -0 == 0, so check the sign through serialization
+        assert_eq!(object["balance"].to_string(), "0");
+    }
+
+    #[test]
+    fn test_map_if_variant() {
+        let wrap = |value: Value| Value::Array(vec![value]);
+
+        let object = Value::from_str(r#"{"a": 1}"#).unwrap();
+        assert_eq!(
+            object.clone().map_if_object(wrap),
+            Value::Array(vec![object.clone()])
+        );
+        assert_eq!(object.clone().map_if_array(wrap), object);
+
+        let array = Value::from_str("[1]").unwrap();
+        assert_eq!(
+            array.clone().map_if_array(wrap),
+            Value::from_str("[[1]]").unwrap()
+        );
+        assert_eq!(array.clone().map_if_object(wrap), array);
+        assert_eq!(Value::Null.map_if_object(wrap), Value::Null);
+    }
+
+    #[test]
+    fn test_transform_object() {
+        fn cents_to_dollars(value: &Value) -> Value {
+            Value::Number(value.as_f64().unwrap_or(0.0) / 100.0)
+        }
+        fn upper(value: &Value) -> Value {
+            Value::String(value.as_str().unwrap_or_default().to_uppercase())
+        }
+
+        let record = Value::from_str(
+            r#"{"user_name": "alice", "amount_cents": 1250, "id": 7, "internal": true}"#,
+        )
+        .unwrap();
+        let reshaped = record
+            .transform_object(&[
+                ("user_name", "name", upper),
+                ("amount_cents", "amount", cents_to_dollars),
+                ("id", "id", Value::clone),
+                ("missing", "ignored", Value::clone),
+            ])
+            .unwrap();
+        assert_eq!(
+            reshaped,
+            Value::from_str(r#"{"name": "ALICE", "amount": 12.5, "id": 7}"#).unwrap()
+        );
+
+        assert_eq!(
+            record.transform_object(&[]),
+            Some(Value::from_str("{}").unwrap())
+        );
+        assert_eq!(Value::from_str("[1]").unwrap().transform_object(&[]), None);
+    }
+
+    #[test]
+    fn test_filter_map_object() {
+        let record =
+            Value::from_str(r#"{"name": "alice", "city": "Oslo", "age": 30, "tags": ["x"]}"#)
+                .unwrap();
+        let strings = record
+            .filter_map_object(|key, value| {
+                value.as_str()?;
+                Some((key.to_uppercase(), value.clone()))
+            })
+            .unwrap();
+        assert_eq!(
+            strings,
+            Value::from_str(r#"{"NAME": "alice", "CITY": "Oslo"}"#).unwrap()
+        );
+
+        assert_eq!(
+            record.filter_map_object(|_, _| None),
+            Some(Value::from_str("{}").unwrap())
+        );
+        assert_eq!(
+            Value::from_str("[1]")
+                .unwrap()
+                .filter_map_object(|key, value| Some((key.to_string(), value.clone()))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_prune_empty() {
+        let mut value = Value::from_str(
+            r#"{
+                "a": {},
+                "b": [[], {}, [[{}]], 1],
+                "c": {"d": {"e": []}, "f": null},
+                "g": {"h": {"i": [{}]}},
+                "s": ""
+            }"#,
+        )
+        .unwrap();
+        value.prune_empty();
+        assert_eq!(
+            value,
+            Value::from_str(r#"{"b": [1], "c": {"f": null}, "s": ""}"#).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_prune_empty_keeps_root() {
+        let mut value = Value::from_str(r#"{"a": {"b": []}, "c": [{}]}"#).unwrap();
+        value.prune_empty();
+        assert_eq!(value, Value::from_str("{}").unwrap());
+
+        let mut value = Value::from_str("[[], [[]]]").unwrap();
+        value.prune_empty();
+        assert_eq!(value, Value::Array(vec![]));
+    }
+
+    #[test]
+    fn test_redact_paths() {
+        let mut value = Value::from_str(
+            r#"{"user": {"name": "bob", "ssn": "123-45-6789", "password": "hunter2"}, "ids": [1, 2]}"#,
+        )
+        .unwrap();
+        value.redact_paths(
+            &[
+                "/user/ssn",
+                "/user/password",
+                "/user/missing",
+                "/ids/5",
+                "bad",
+            ],
+            Value::String("[redacted]".to_string()),
+        );
+        assert_eq!(
+            value,
+            Value::from_str(
+                r#"{"user": {"name": "bob", "ssn": "[redacted]", "password": "[redacted]"}, "ids": [1, 2]}"#
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_replace_all_does_not_revisit_replacements() {
+        let mut value = Value::from_str("[1, [2, 3]]").unwrap();
+        // Each replacement is itself an array containing a number; it must
+        // not be replaced again
+        value.replace_all(
+            |v| matches!(v, Value::Number(_)),
+            |v| Value::Array(vec![v.clone()]),
+        );
+        assert_eq!(value, Value::from_str("[[1], [[2], [3]]]").unwrap());
+
+        let mut root = Value::Null;
+        root.replace_all(|v| *v == Value::Null, |_| Value::Boolean(false));
+        assert_eq!(root, Value::Boolean(false));
+    }
+
+    #[test]
+    fn test_dedup_array() {
+        let mut value = Value::from_str(
+            r#"[1, 2, 1, {"a": [1]}, 3, {"a": [1]}, {"a": [2]}, 2, [1, 2], [1, 2]]"#,
+        )
+        .unwrap();
+        value.dedup_array();
+        assert_eq!(
+            value,
+            Value::from_str(r#"[1, 2, {"a": [1]}, 3, {"a": [2]}, [1, 2]]"#).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_dedup_array_nan_and_non_arrays() {
+        let mut value = Value::Array(vec![
+            Value::Number(f64::NAN),
+            Value::Number(f64::NAN),
+            Value::Number(0.0),
+            Value::Number(-0.0),
+        ]);
+        value.dedup_array();
+        let Value::Array(elements) = &value else {
+            panic!("Expected array");
+        };
+        assert_eq!(elements.len(), 3);
+
+        let mut object = Value::from_str(r#"{"a": [1, 1]}"#).unwrap();
+        object.dedup_array();
+        assert_eq!(object, Value::from_str(r#"{"a": [1, 1]}"#).unwrap());
+    }
+
+    fn number_bits(value: &Value) -> u64 {
+        match value {
+            Value::Number(n) => n.to_bits(),
+            _ => panic!("Expected number"),
+        }
+    }
+
+    #[test]
+    fn test_normalize_negative_zero() {
+        let mut value = Value::from_str("-0.0").unwrap();
+        assert!(matches!(value, Value::Number(n) if n.is_sign_negative()));
+        value.normalize_numbers();
+        assert_eq!(number_bits(&value), 0.0f64.to_bits());
+    }
+
+    #[test]
+    fn test_normalize_integral_floats() {
+        let mut float = Value::from_str("1.0").unwrap();
+        let mut integer = Value::from_str("1").unwrap();
+        float.normalize_numbers();
+        integer.normalize_numbers();
+        assert_eq!(number_bits(&float), number_bits(&integer));
+    }
+
+    #[test]
+    fn test_normalize_nested_numbers() {
+        let mut value = Value::from_str(r#"{"a": [-0, 2.5, {"b": -0e3}], "c": -1}"#).unwrap();
+        value.normalize_numbers();
+
+        let Value::Object(object) = &value else {
+            panic!("Expected object");
+        };
+        let Some(Value::Array(a)) = object.get("a") else {
+            panic!("Expected array");
+        };
+        assert_eq!(number_bits(&a[0]), 0.0f64.to_bits());
+        assert_eq!(a[1], Value::Number(2.5));
+        let Value::Object(inner) = &a[2] else {
+            panic!("Expected object");
+        };
+        assert_eq!(number_bits(&inner["b"]), 0.0f64.to_bits());
+        assert_eq!(object["c"], Value::Number(-1.0));
+    }
+}
@@ -0,0 +1,722 @@
+use std::collections::HashMap;
+
+use crate::pointer::push_token;
+use crate::Value;
+
+impl Value {
+    /// Collect every string value in the document, depth first
+    ///
+    /// With `include_keys`, object keys are collected too, each one just
+    /// before the strings found under it. Object members are visited in the
+    /// map's iteration order, so the result is unordered across keys.
+    pub fn extract_strings(&self, include_keys: bool) -> Vec<String> {
+        let mut strings = Vec::new();
+        self.extract_strings_into(include_keys, &mut strings);
+        strings
+    }
+
+    /// Collect every number in the document, depth first, for quick sums,
+    /// means and maxima
+    ///
+    /// The numeric counterpart of `extract_strings`: array elements come out
+    /// in order, object members in the map's iteration order.
+    pub fn collect_numbers(&self) -> Vec<f64> {
+        let mut numbers = Vec::new();
+        self.collect_numbers_into(&mut numbers);
+        numbers
+    }
+
+    /// Collect every node, the root included, for which `predicate` is true
+    ///
+    /// Nodes are visited depth first with each container before its
+    /// children, so array elements come out in order while object members
+    /// follow the map's iteration order.
+    pub fn select(&self, predicate: impl Fn(&Value) -> bool) -> Vec<&Value> {
+        let mut selected = Vec::new();
+        self.select_into(&predicate, &mut selected);
+        selected
+    }
+
+    /// List every node in the tree with its JSON Pointer, starting with the
+    /// root at `""`
+    ///
+    /// Nodes come out in the same order as `select`: depth first, each
+    /// container before its children.
+    pub fn iter_pointers(&self) -> Vec<(String, &Value)> {
+        let mut nodes = Vec::new();
+        self.pointers_into(&mut String::new(), &mut nodes);
+        nodes
+    }
+
+    fn pointers_into<'a>(&'a self, path: &mut String, nodes: &mut Vec<(String, &'a Value)>) {
+        nodes.push((path.clone(), self));
+
+        let len = path.len();
+        match self {
+            Value::Array(elements) => {
+                for (i, element) in elements.iter().enumerate() {
+                    push_token(path, &i.to_string());
+                    element.pointers_into(path, nodes);
+                    path.truncate(len);
+                }
+            }
+            Value::Object(object) => {
+                for (key, value) in object {
+                    push_token(path, key);
+                    value.pointers_into(path, nodes);
+                    path.truncate(len);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// JSON Pointer to the first node, the root included, for which
+    /// `predicate` is true
+    ///
+    /// Nodes are searched in the same order as `iter_pointers`, so among
+    /// several matches in different members of an object, which one is found
+    /// first follows the map's iteration order.
+    pub fn find_path(&self, predicate: impl Fn(&Value) -> bool) -> Option<String> {
+        let mut path = String::new();
+        self.find_path_into(&predicate, &mut path).then_some(path)
+    }
+
+    /// Search for a match below `path`, leaving `path` pointing at it and
+    /// returning `true` when one is found
+    fn find_path_into(&self, predicate: &impl Fn(&Value) -> bool, path: &mut String) -> bool {
+        if predicate(self) {
+            return true;
+        }
+
+        let len = path.len();
+        match self {
+            Value::Array(elements) => {
+                for (i, element) in elements.iter().enumerate() {
+                    push_token(path, &i.to_string());
+                    if element.find_path_into(predicate, path) {
+                        return true;
+                    }
+                    path.truncate(len);
+                }
+            }
+            Value::Object(object) => {
+                for (key, value) in object {
+                    push_token(path, key);
+                    if value.find_path_into(predicate, path) {
+                        return true;
+                    }
+                    path.truncate(len);
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Check that no string in the tree, object keys included, holds a
+    /// code point that signals damaged or non-interchangeable text,
+    /// returning the JSON Pointers of any that do
+    ///
+    /// A Rust `String` is always valid UTF-8, so a lone surrogate cannot
+    /// survive in one; lossy decoding such as `String::from_utf16_lossy`
+    /// replaces it with U+FFFD instead. This rejects that replacement
+    /// character and the Unicode noncharacters (U+FDD0 to U+FDEF, and the
+    /// last two code points of every plane, such as U+FFFE and U+FFFF). Note
+    /// that an input escape like `\ufffd` parses to U+FFFD and fails too.
+    /// A key that fails is reported as the pointer to its member.
+    pub fn validate_strings(&self) -> Result<(), Vec<String>> {
+        let invalid = self.invalid_string_paths(&|s| !s.chars().any(is_suspect_char));
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid)
+        }
+    }
+
+    /// Pointers to the strings and keys for which `is_valid` is false
+    fn invalid_string_paths(&self, is_valid: &impl Fn(&str) -> bool) -> Vec<String> {
+        let mut invalid = Vec::new();
+        self.invalid_strings_into(is_valid, &mut String::new(), &mut invalid);
+        invalid
+    }
+
+    fn invalid_strings_into(
+        &self,
+        is_valid: &impl Fn(&str) -> bool,
+        path: &mut String,
+        invalid: &mut Vec<String>,
+    ) {
+        let len = path.len();
+        match self {
+            Value::String(s) if !is_valid(s) => invalid.push(path.clone()),
+            Value::Array(elements) => {
+                for (i, element) in elements.iter().enumerate() {
+                    push_token(path, &i.to_string());
+                    element.invalid_strings_into(is_valid, path, invalid);
+                    path.truncate(len);
+                }
+            }
+            Value::Object(object) => {
+                for (key, value) in object {
+                    push_token(path, key);
+                    if !is_valid(key) {
+                        invalid.push(path.clone());
+                    }
+                    value.invalid_strings_into(is_valid, path, invalid);
+                    path.truncate(len);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Count the nodes, the root included, for which `predicate` is true,
+    /// without collecting them
+    pub fn count_matching(&self, predicate: impl Fn(&Value) -> bool) -> usize {
+        self.count_matching_with(&predicate)
+    }
+
+    /// Check whether `subset` appears within `self` as a substructure
+    ///
+    /// Objects match when every member of `subset` is present in `self` with
+    /// a value that in turn contains the subset's value; extra members in
+    /// `self` are ignored. Arrays match when the subset's elements are
+    /// contained, in order, by a subsequence of `self`'s elements, so
+    /// `[1, 2, 3]` contains `[1, 3]` but not `[3, 1]`. Scalars must be equal.
+    pub fn contains(&self, subset: &Value) -> bool {
+        match (self, subset) {
+            (Value::Object(object), Value::Object(wanted)) => wanted.iter().all(|(key, value)| {
+                object
+                    .get(key)
+                    .is_some_and(|present| present.contains(value))
+            }),
+            (Value::Array(elements), Value::Array(wanted)) => {
+                // Matching each wanted element as early as possible never
+                // rules out a later match
+                let mut remaining = elements.iter();
+                wanted
+                    .iter()
+                    .all(|value| remaining.any(|element| element.contains(value)))
+            }
+            _ => self == subset,
+        }
+    }
+
+    /// Check whether `self` and `other` have the same structure, ignoring
+    /// the contents of scalars
+    ///
+    /// Scalars match when they are the same variant, so any two numbers
+    /// match but a number and a string do not. Objects match when they have
+    /// the same key set and the values under each key match. Arrays of the
+    /// same length match when their elements match pairwise. Arrays of
+    /// different lengths match only when both are uniform lists: every
+    /// element of each matches the first element of the longer one, so
+    /// `[1, 2, 3]` matches `[4]` and `[]`, while `[1, "a"]` matches neither
+    /// `["b", 2]` nor `[1]`.
+    pub fn same_shape(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Array(a), Value::Array(b)) => {
+                if a.len() == b.len() {
+                    return a.iter().zip(b).all(|(x, y)| x.same_shape(y));
+                }
+                let longer = if a.len() > b.len() { a } else { b };
+                a.iter()
+                    .chain(b)
+                    .all(|element| element.same_shape(&longer[0]))
+            }
+            (Value::Object(a), Value::Object(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .all(|(key, value)| b.get(key).is_some_and(|v| value.same_shape(v)))
+            }
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+
+    /// Length in characters of the longest string in the tree, object keys
+    /// included; 0 if there are none
+    pub fn max_string_length(&self) -> usize {
+        self.fold_strings(0, &|longest, s| longest.max(s.chars().count()))
+    }
+
+    /// Total UTF-8 size in bytes of every string in the tree, object keys
+    /// included
+    pub fn total_string_bytes(&self) -> usize {
+        self.fold_strings(0, &|total, s| total + s.len())
+    }
+
+    /// Count the nodes at each depth, for spotting documents that are
+    /// unexpectedly deep or wide
+    ///
+    /// The root is at depth 0 and the children of a container are one level
+    /// below it. Every node counts, scalars included; depths with no nodes
+    /// are absent.
+    pub fn depth_histogram(&self) -> HashMap<usize, usize> {
+        let mut histogram = HashMap::new();
+        self.depth_histogram_into(0, &mut histogram);
+        histogram
+    }
+
+    fn depth_histogram_into(&self, depth: usize, histogram: &mut HashMap<usize, usize>) {
+        *histogram.entry(depth).or_insert(0) += 1;
+
+        match self {
+            Value::Array(elements) => {
+                for element in elements {
+                    element.depth_histogram_into(depth + 1, histogram);
+                }
+            }
+            Value::Object(object) => {
+                for value in object.values() {
+                    value.depth_histogram_into(depth + 1, histogram);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Number of elements in the longest array in the tree; 0 if there are
+    /// none
+    pub fn max_array_length(&self) -> usize {
+        match self {
+            Value::Array(elements) => elements
+                .iter()
+                .map(Value::max_array_length)
+                .fold(elements.len(), usize::max),
+            Value::Object(object) => object
+                .values()
+                .map(Value::max_array_length)
+                .max()
+                .unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    /// Fold `f` over every string value and object key in the tree
+    fn fold_strings(&self, acc: usize, f: &impl Fn(usize, &str) -> usize) -> usize {
+        match self {
+            Value::String(s) => f(acc, s),
+            Value::Array(elements) => elements
+                .iter()
+                .fold(acc, |acc, element| element.fold_strings(acc, f)),
+            Value::Object(object) => object
+                .iter()
+                .fold(acc, |acc, (key, value)| value.fold_strings(f(acc, key), f)),
+            _ => acc,
+        }
+    }
+
+    fn count_matching_with(&self, predicate: &impl Fn(&Value) -> bool) -> usize {
+        let own = usize::from(predicate(self));
+        let children = match self {
+            Value::Array(elements) => elements
+                .iter()
+                .map(|element| element.count_matching_with(predicate))
+                .sum(),
+            Value::Object(object) => object
+                .values()
+                .map(|value| value.count_matching_with(predicate))
+                .sum(),
+            _ => 0,
+        };
+        own + children
+    }
+
+    fn select_into<'a>(
+        &'a self,
+        predicate: &impl Fn(&Value) -> bool,
+        selected: &mut Vec<&'a Value>,
+    ) {
+        if predicate(self) {
+            selected.push(self);
+        }
+
+        match self {
+            Value::Array(elements) => {
+                for element in elements {
+                    element.select_into(predicate, selected);
+                }
+            }
+            Value::Object(object) => {
+                for value in object.values() {
+                    value.select_into(predicate, selected);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn collect_numbers_into(&self, numbers: &mut Vec<f64>) {
+        match self {
+            Value::Number(n) => numbers.push(*n),
+            Value::Array(elements) => {
+                for element in elements {
+                    element.collect_numbers_into(numbers);
+                }
+            }
+            Value::Object(object) => {
+                for value in object.values() {
+                    value.collect_numbers_into(numbers);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn extract_strings_into(&self, include_keys: bool, strings: &mut Vec<String>) {
+        match self {
+            Value::String(s) => strings.push(s.clone()),
+            Value::Array(elements) => {
+                for element in elements {
+                    element.extract_strings_into(include_keys, strings);
+                }
+            }
+            Value::Object(object) => {
+                for (key, value) in object {
+                    if include_keys {
+                        strings.push(key.clone());
+                    }
+                    value.extract_strings_into(include_keys, strings);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The replacement character, or a noncharacter
+fn is_suspect_char(c: char) -> bool {
+    let c = u32::from(c);
+    c == 0xFFFD || (0xFDD0..=0xFDEF).contains(&c) || c & 0xFFFE == 0xFFFE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut strings: Vec<String>) -> Vec<String> {
+        strings.sort();
+        strings
+    }
+
+    #[test]
+    fn test_extract_strings() {
+        let value = Value::from_str(
+            r#"{"name": "Alice", "tags": ["x", 1, ["y"]], "meta": {"note": "z", "n": null}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            sorted(value.extract_strings(false)),
+            vec!["Alice", "x", "y", "z"]
+        );
+        assert_eq!(
+            sorted(value.extract_strings(true)),
+            vec!["Alice", "meta", "n", "name", "note", "tags", "x", "y", "z"]
+        );
+    }
+
+    #[test]
+    fn test_collect_numbers() {
+        let value = Value::from_str(
+            r#"{"a": 1, "b": [2.5, "3", [4, {"c": -5}]], "d": {"e": null, "f": [0]}}"#,
+        )
+        .unwrap();
+        let mut numbers = value.collect_numbers();
+        numbers.sort_by(f64::total_cmp);
+        assert_eq!(numbers, vec![-5.0, 0.0, 1.0, 2.5, 4.0]);
+        assert_eq!(numbers.iter().sum::<f64>(), 2.5);
+
+        // Array elements keep their order
+        let value = Value::from_str("[3, [1, 2], 0]").unwrap();
+        assert_eq!(value.collect_numbers(), vec![3.0, 1.0, 2.0, 0.0]);
+        assert_eq!(Value::Number(7.0).collect_numbers(), vec![7.0]);
+        assert!(Value::from_str(r#"{"a": "1"}"#)
+            .unwrap()
+            .collect_numbers()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_select_strings() {
+        let value = Value::from_str(
+            r#"{"a": "x", "b": [1, "y", {"c": "z", "d": null}], "e": {"f": ["w"]}}"#,
+        )
+        .unwrap();
+        let mut strings: Vec<&Value> = value.select(|v| matches!(v, Value::String(_)));
+        strings.sort_by_key(|v| format!("{:?}", v));
+
+        let expected: Vec<Value> = ["w", "x", "y", "z"]
+            .iter()
+            .map(|s| Value::String(s.to_string()))
+            .collect();
+        assert_eq!(strings, expected.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_select_order_and_root() {
+        let value = Value::from_str("[150, [7, 300], 101]").unwrap();
+        let big = value.select(|v| matches!(v, Value::Number(n) if *n > 100.0));
+        assert_eq!(
+            big,
+            vec![
+                &Value::Number(150.0),
+                &Value::Number(300.0),
+                &Value::Number(101.0)
+            ]
+        );
+        assert_eq!(value.select(|v| matches!(v, Value::Array(_))).len(), 2);
+        assert_eq!(value.select(|_| true)[0], &value);
+    }
+
+    #[test]
+    fn test_iter_pointers() {
+        let value = Value::from_str(r#"{"a": [1, {"b/c": null}], "d~": []}"#).unwrap();
+        let pointers = value.iter_pointers();
+
+        assert_eq!(pointers[0], (String::new(), &value));
+        for (ptr, node) in &pointers {
+            assert_eq!(value.pointer(ptr), Some(*node), "{}", ptr);
+        }
+
+        let mut paths: Vec<&str> = pointers.iter().map(|(ptr, _)| ptr.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["", "/a", "/a/0", "/a/1", "/a/1/b~1c", "/d~0"]);
+        assert_eq!(
+            Value::from_str("[[7]]").unwrap().iter_pointers(),
+            vec![
+                (String::new(), &Value::from_str("[[7]]").unwrap()),
+                ("/0".to_string(), &Value::from_str("[7]").unwrap()),
+                ("/0/0".to_string(), &Value::Number(7.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_path() {
+        let value = Value::from_str(
+            r#"{"users": [{"name": "ann"}, {"name": "bob", "tags": ["x", "needle"]}], "a/b": 1}"#,
+        )
+        .unwrap();
+        let needle = Value::String("needle".to_string());
+
+        assert_eq!(
+            value.find_path(|v| *v == needle),
+            Some("/users/1/tags/1".to_string())
+        );
+        assert_eq!(
+            value.find_path(|v| *v == Value::Number(1.0)),
+            Some("/a~1b".to_string())
+        );
+        assert_eq!(value.find_path(|v| v.is_null()), None);
+        assert_eq!(value.find_path(|_| true), Some(String::new()));
+    }
+
+    #[test]
+    fn test_find_path_takes_first_array_match() {
+        let value = Value::from_str(r#"[[0, 5], 5, [5]]"#).unwrap();
+        assert_eq!(
+            value.find_path(|v| *v == Value::Number(5.0)),
+            Some("/0/1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_strings() {
+        let valid = Value::Array(vec![
+            Value::String("plain".to_string()),
+            Value::String(String::from_utf16_lossy(&[0xD83D, 0xDE00])),
+            Value::object_of([(
+                "日本".to_string(),
+                Value::String("\u{10FFFD}\0\u{FDCF}\u{FFFC}".to_string()),
+            )]),
+        ]);
+        assert_eq!(valid.validate_strings(), Ok(()));
+        assert_eq!(Value::Null.validate_strings(), Ok(()));
+
+        // A lone surrogate decoded lossily, noncharacters, and a bad key
+        let value = Value::Array(vec![
+            Value::String("plain".to_string()),
+            Value::String(String::from_utf16_lossy(&[0xD83D, 0xDE00, 0xD800])),
+            Value::object_of([
+                ("ok".to_string(), Value::String("x\u{FFFE}".to_string())),
+                ("\u{FDD0}".to_string(), Value::Number(1.0)),
+                ("fine".to_string(), Value::String("y".to_string())),
+            ]),
+            Value::String("\u{10FFFF}".to_string()),
+        ]);
+        let mut invalid = value.validate_strings().unwrap_err();
+        invalid.sort();
+        assert_eq!(invalid, vec!["/1", "/2/ok", "/2/\u{FDD0}", "/3"]);
+
+        assert_eq!(
+            Value::from_str(r#"{"k": "\ufffd"}"#)
+                .unwrap()
+                .validate_strings(),
+            Err(vec!["/k".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_invalid_string_paths() {
+        let value =
+            Value::from_str(r#"{"ok": ["fine", "bad"], "bad": 1, "a/b": {"c": "bad"}, "n": null}"#)
+                .unwrap();
+        let mut invalid = value.invalid_string_paths(&|s| s != "bad");
+        invalid.sort();
+        assert_eq!(invalid, vec!["/a~1b/c", "/bad", "/ok/1"]);
+        assert_eq!(
+            Value::String("bad".to_string()).invalid_string_paths(&|s| s != "bad"),
+            vec![String::new()]
+        );
+    }
+
+    #[test]
+    fn test_count_matching() {
+        let value = Value::from_str(
+            r#"{"a": null, "b": [1, null, {"c": 2.5, "d": null}], "e": {"f": [3, "4"]}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(value.count_matching(|v| v.is_null()), 3);
+        assert_eq!(value.count_matching(|v| v.as_f64().is_some()), 3);
+        assert_eq!(value.count_matching(|_| true), 12);
+        assert_eq!(Value::Null.count_matching(|v| v.is_null()), 1);
+        assert_eq!(value.count_matching(|v| v.as_bool().is_some()), 0);
+    }
+
+    #[test]
+    fn test_contains_subset() {
+        let response = Value::from_str(
+            r#"{"status": "ok", "data": {"id": 7, "tags": ["a", "b", "c"], "items": [{"n": 1, "x": true}, {"n": 2}]}}"#,
+        )
+        .unwrap();
+
+        for subset in [
+            r#"{"status": "ok"}"#,
+            r#"{"data": {"id": 7}}"#,
+            r#"{"data": {"tags": ["a", "c"]}}"#,
+            r#"{"data": {"items": [{"n": 2}]}}"#,
+            r#"{"data": {"items": [{"x": true}, {}]}}"#,
+            "{}",
+        ] {
+            assert!(
+                response.contains(&Value::from_str(subset).unwrap()),
+                "{}",
+                subset
+            );
+        }
+    }
+
+    #[test]
+    fn test_contains_rejects_mismatches() {
+        let response =
+            Value::from_str(r#"{"status": "ok", "tags": ["a", "b", "c"], "n": 1}"#).unwrap();
+
+        for subset in [
+            r#"{"missing": null}"#,
+            r#"{"status": "error"}"#,
+            r#"{"tags": ["c", "a"]}"#,
+            r#"{"tags": ["a", "a"]}"#,
+            r#"{"n": "1"}"#,
+            r#"{"tags": {}}"#,
+        ] {
+            assert!(
+                !response.contains(&Value::from_str(subset).unwrap()),
+                "{}",
+                subset
+            );
+        }
+        assert!(Value::Number(1.0).contains(&Value::Number(1.0)));
+        assert!(!Value::Null.contains(&Value::from_str("{}").unwrap()));
+    }
+
+    #[test]
+    fn test_same_shape() {
+        let shape = |a: &str, b: &str| {
+            Value::from_str(a)
+                .unwrap()
+                .same_shape(&Value::from_str(b).unwrap())
+        };
+
+        assert!(shape(
+            r#"{"id": 1, "name": "a", "tags": ["x"], "meta": {"ok": true}}"#,
+            r#"{"id": 2, "name": "b", "tags": ["y"], "meta": {"ok": false}}"#
+        ));
+        assert!(shape("null", "null"));
+        assert!(!shape("1", r#""1""#));
+        assert!(!shape(r#"{"a": 1}"#, r#"{"a": 1, "b": 2}"#));
+        assert!(!shape(r#"{"a": 1}"#, r#"{"b": 1}"#));
+        assert!(!shape(r#"{"a": 1}"#, r#"{"a": "1"}"#));
+        assert!(!shape("[]", "{}"));
+    }
+
+    #[test]
+    fn test_same_shape_arrays() {
+        let shape = |a: &str, b: &str| {
+            Value::from_str(a)
+                .unwrap()
+                .same_shape(&Value::from_str(b).unwrap())
+        };
+
+        // Same length: pairwise
+        assert!(shape(r#"[1, "a", null]"#, r#"[2, "b", null]"#));
+        assert!(!shape(r#"[1, "a"]"#, r#"["b", 2]"#));
+
+        // Different lengths: both must be uniform lists of one shape
+        assert!(shape(
+            r#"[{"id": 1}, {"id": 2}, {"id": 3}]"#,
+            r#"[{"id": 4}]"#
+        ));
+        assert!(shape("[1, 2, 3]", "[]"));
+        assert!(!shape(r#"[1, "a"]"#, "[1]"));
+        assert!(!shape("[1, 2]", r#"["a"]"#));
+        assert!(!shape(r#"[{"id": 1}, {"name": "x"}]"#, "[]"));
+    }
+
+    #[test]
+    fn test_size_introspection() {
+        let value = Value::from_str(
+            r#"{"name": "Zoë", "list": [1, 2, [3, 4, 5, 6], {"k": ["abcdefg"]}], "long_key_name": null}"#,
+        )
+        .unwrap();
+
+        // "long_key_name" is the longest string, at 13 characters
+        assert_eq!(value.max_string_length(), 13);
+        assert_eq!(value.max_array_length(), 4);
+        // Keys: name 4, list 4, k 1, long_key_name 13; values: Zoë 4, abcdefg 7
+        assert_eq!(value.total_string_bytes(), 33);
+
+        let scalar = Value::Number(1.0);
+        assert_eq!(scalar.max_string_length(), 0);
+        assert_eq!(scalar.max_array_length(), 0);
+        assert_eq!(scalar.total_string_bytes(), 0);
+        assert_eq!(Value::String("日本".to_string()).max_string_length(), 2);
+        assert_eq!(Value::String("日本".to_string()).total_string_bytes(), 6);
+    }
+
+    #[test]
+    fn test_depth_histogram() {
+        let value =
+            Value::from_str(r#"{"a": 1, "b": [true, {"c": null, "d": []}], "e": {"f": "x"}}"#)
+                .unwrap();
+        let expected: HashMap<usize, usize> =
+            [(0, 1), (1, 3), (2, 3), (3, 2)].into_iter().collect();
+        assert_eq!(value.depth_histogram(), expected);
+
+        let total: usize = value.depth_histogram().values().sum();
+        assert_eq!(total, value.count_matching(|_| true));
+        assert_eq!(
+            Value::Null.depth_histogram(),
+            [(0, 1)].into_iter().collect::<HashMap<_, _>>()
+        );
+    }
+
+    #[test]
+    fn test_extract_strings_keeps_array_order() {
+        let value = Value::from_str(r#"["b", "a", "c"]"#).unwrap();
+        assert_eq!(value.extract_strings(true), vec!["b", "a", "c"]);
+        assert!(Value::Number(1.0).extract_strings(true).is_empty());
+    }
+}
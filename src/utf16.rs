@@ -0,0 +1,125 @@
+use crate::error::{ParseError, ParseErrorKind};
+use crate::Value;
+
+const BOM: u16 = 0xFEFF;
+
+impl Value {
+    /// Parse JSON given as UTF-16 code units
+    ///
+    /// The units are decoded to UTF-8 first, dropping a leading byte order
+    /// mark, so error offsets, lines and columns refer to the decoded text.
+    /// An unpaired surrogate is reported as `ParseErrorKind::InvalidUtf16`.
+    pub fn from_utf16(units: &[u16]) -> Result<Self, ParseError> {
+        let units = units.strip_prefix(&[BOM]).unwrap_or(units);
+        Value::from_str(&decode(units.iter().copied())?)
+    }
+
+    /// Parse UTF-16 encoded bytes, taking the byte order from a leading BOM
+    ///
+    /// `FF FE` selects little-endian and `FE FF` big-endian. Without a BOM
+    /// the input is read as little-endian, which is what Windows APIs
+    /// produce.
+    pub fn from_utf16_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        if !bytes.len().is_multiple_of(2) {
+            return Err(ParseError::with_kind(
+                ParseErrorKind::InvalidUtf16,
+                "Invalid UTF-16: odd number of bytes",
+                "",
+                0,
+            ));
+        }
+
+        let (bytes, big_endian) = match bytes {
+            [0xFE, 0xFF, rest @ ..] => (rest, true),
+            [0xFF, 0xFE, rest @ ..] => (rest, false),
+            _ => (bytes, false),
+        };
+        let units = bytes.chunks_exact(2).map(|pair| {
+            let pair = [pair[0], pair[1]];
+            if big_endian {
+                u16::from_be_bytes(pair)
+            } else {
+                u16::from_le_bytes(pair)
+            }
+        });
+
+        Value::from_str(&decode(units)?)
+    }
+}
+
+fn decode(units: impl Iterator<Item = u16>) -> Result<String, ParseError> {
+    let mut text = String::new();
+    for c in char::decode_utf16(units) {
+        match c {
+            Ok(c) => text.push(c),
+            Err(e) => {
+                return Err(ParseError::with_kind(
+                    ParseErrorKind::InvalidUtf16,
+                    format!(
+                        "Invalid UTF-16: unpaired surrogate 0x{:04X}",
+                        e.unpaired_surrogate()
+                    ),
+                    &text,
+                    text.len(),
+                ))
+            }
+        }
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON: &str = r#"{"name": "Zoë", "emoji": "😀", "n": [1, 2]}"#;
+
+    #[test]
+    fn test_from_utf16() {
+        let units: Vec<u16> = JSON.encode_utf16().collect();
+        assert_eq!(
+            Value::from_utf16(&units).unwrap(),
+            Value::from_str(JSON).unwrap()
+        );
+
+        let with_bom: Vec<u16> = std::iter::once(BOM).chain(units).collect();
+        assert_eq!(
+            Value::from_utf16(&with_bom).unwrap(),
+            Value::from_str(JSON).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_from_utf16_bytes() {
+        let units: Vec<u16> = JSON.encode_utf16().collect();
+        let le: Vec<u8> = units.iter().flat_map(|u| u.to_le_bytes()).collect();
+        let be: Vec<u8> = units.iter().flat_map(|u| u.to_be_bytes()).collect();
+        let expected = Value::from_str(JSON).unwrap();
+
+        assert_eq!(Value::from_utf16_bytes(&le).unwrap(), expected);
+        let le_bom = [&[0xFF, 0xFE][..], &le].concat();
+        assert_eq!(Value::from_utf16_bytes(&le_bom).unwrap(), expected);
+        let be_bom = [&[0xFE, 0xFF][..], &be].concat();
+        assert_eq!(Value::from_utf16_bytes(&be_bom).unwrap(), expected);
+
+        let err = Value::from_utf16_bytes(&le[1..]).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidUtf16);
+        assert_eq!(err.message, "Invalid UTF-16: odd number of bytes");
+    }
+
+    #[test]
+    fn test_invalid_utf16() {
+        let mut units: Vec<u16> = r#"["ab"#.encode_utf16().collect();
+        units.push(0xD800);
+        units.extend(r#""]"#.encode_utf16());
+
+        let err = Value::from_utf16(&units).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidUtf16);
+        assert_eq!(err.message, "Invalid UTF-16: unpaired surrogate 0xD800");
+        assert_eq!((err.offset, err.column), (4, 5));
+
+        let units: Vec<u16> = "[1,]".encode_utf16().collect();
+        let err = Value::from_utf16(&units).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::Syntax);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::ser::{write_number, write_string, SerializeOptions};
+use crate::Value;
+
+impl Value {
+    /// Write the value as YAML-style block text, for people reading config
+    ///
+    /// Objects become `key: value` lines with keys in sorted order, arrays
+    /// become `- item` lines, and nested containers are indented by two
+    /// spaces. Empty containers are written inline as `{}` and `[]`.
+    /// Strings are written plainly unless they could be misread, e.g. as a
+    /// number, a boolean or YAML syntax, in which case they are quoted with
+    /// JSON escapes, which YAML accepts. This is a readable dump of the JSON
+    /// data model, not a full YAML emitter. Every line ends with `\n`.
+    pub fn to_yaml(&self) -> String {
+        let mut out = String::new();
+        write_node(&mut out, self, 0);
+        out
+    }
+}
+
+/// Write `value` starting at the current position, which the caller has
+/// already indented; any further lines are indented by `indent` spaces
+fn write_node(out: &mut String, value: &Value, indent: usize) {
+    match value {
+        Value::Array(elements) if !elements.is_empty() => {
+            for (i, element) in elements.iter().enumerate() {
+                if i > 0 {
+                    push_indent(out, indent);
+                }
+                out.push_str("- ");
+                // A nested container starts on the dash's line
+                write_node(out, element, indent + 2);
+            }
+        }
+        Value::Object(object) if !object.is_empty() => {
+            let mut members: Vec<(&String, &Value)> = object.iter().collect();
+            members.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+            for (i, (key, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    push_indent(out, indent);
+                }
+                write_scalar_string(out, key);
+                out.push(':');
+                if is_block(value) {
+                    out.push('\n');
+                    push_indent(out, indent + 2);
+                } else {
+                    out.push(' ');
+                }
+                write_node(out, value, indent + 2);
+            }
+        }
+        Value::Array(_) => out.push_str("[]\n"),
+        Value::Object(_) => out.push_str("{}\n"),
+        Value::Null => out.push_str("null\n"),
+        Value::Boolean(b) => {
+            out.push_str(if *b { "true\n" } else { "false\n" });
+        }
+        Value::Number(n) => {
+            write_number(out, *n);
+            out.push('\n');
+        }
+        Value::String(s) => {
+            write_scalar_string(out, s);
+            out.push('\n');
+        }
+    }
+}
+
+/// Whether `value` is written as indented lines rather than on one line
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Array(elements) => !elements.is_empty(),
+        Value::Object(object) => !object.is_empty(),
+        _ => false,
+    }
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+    out.extend(std::iter::repeat_n(' ', indent));
+}
+
+/// Write a string plainly, or quoted if YAML would read it as something else
+fn write_scalar_string(out: &mut String, s: &str) {
+    if needs_quotes(s) {
+        write_string(out, s, &SerializeOptions::default());
+    } else {
+        out.push_str(s);
+    }
+}
+
+fn needs_quotes(s: &str) -> bool {
+    let Some(first) = s.chars().next() else {
+        return true;
+    };
+
+    // Leading characters with a meaning of their own, and surrounding
+    // whitespace that a plain scalar would lose
+    if "-?:,[]{}#&*!|>'\"%@`".contains(first) || s.trim() != s {
+        return true;
+    }
+
+    // Words and numbers that YAML reads as other types
+    let lower = s.to_ascii_lowercase();
+    if matches!(
+        lower.as_str(),
+        "~" | "null" | "true" | "false" | "yes" | "no" | "on" | "off" | "y" | "n"
+    ) || lower.starts_with('.')
+        || s.parse::<f64>().is_ok()
+    {
+        return true;
+    }
+
+    s.contains(": ") || s.contains(" #") || s.ends_with(':') || s.chars().any(char::is_control)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nested_object() {
+        let value = Value::from_str(
+            r#"{
+                "name": "web",
+                "port": 8080,
+                "debug": false,
+                "hosts": ["a.example.com", "b.example.com"],
+                "db": {"user": "admin", "pool": {"min": 1, "max": 10}},
+                "routes": [{"path": "/", "auth": null}, [1, 2]],
+                "tags": [],
+                "env": {}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value.to_yaml(),
+            "\
+db:
+  pool:
+    max: 10
+    min: 1
+  user: admin
+debug: false
+env: {}
+hosts:
+  - a.example.com
+  - b.example.com
+name: web
+port: 8080
+routes:
+  - auth: null
+    path: /
+  - - 1
+    - 2
+tags: []
+"
+        );
+    }
+
+    #[test]
+    fn test_scalars() {
+        assert_eq!(Value::Null.to_yaml(), "null\n");
+        assert_eq!(Value::Number(2.5).to_yaml(), "2.5\n");
+        assert_eq!(Value::Array(vec![]).to_yaml(), "[]\n");
+        assert_eq!(
+            Value::from_str(r#"["hello world", true]"#)
+                .unwrap()
+                .to_yaml(),
+            "- hello world\n- true\n"
+        );
+    }
+
+    #[test]
+    fn test_strings_quoted_only_when_needed() {
+        let plain = [
+            "hello",
+            "a.example.com",
+            "/usr/bin",
+            "v1.2.3",
+            "日本",
+            "say \"hi\"",
+        ];
+        for s in plain {
+            assert_eq!(Value::String(s.to_string()).to_yaml(), format!("{}\n", s));
+        }
+
+        let quoted = [
+            ("", r#""""#),
+            ("true", r#""true""#),
+            ("No", r#""No""#),
+            ("null", r#""null""#),
+            ("42", r#""42""#),
+            ("1e3", r#""1e3""#),
+            (".inf", r#"".inf""#),
+            ("- item", r#""- item""#),
+            ("key: value", r#""key: value""#),
+            (" padded", r#"" padded""#),
+            ("two\nlines", r#""two\nlines""#),
+            ("\"hi\"", r#""\"hi\"""#),
+        ];
+        for (s, expected) in quoted {
+            assert_eq!(
+                Value::String(s.to_string()).to_yaml(),
+                format!("{}\n", expected)
+            );
+        }
+
+        let value = Value::from_str(r#"{"on": 1, "a b": 2}"#).unwrap();
+        assert_eq!(value.to_yaml(), "a b: 2\n\"on\": 1\n");
+    }
+}